@@ -366,6 +366,15 @@ impl<T: Config> Pallet<T> {
 
 }
 
+/// Provides the id of the current BEEFY validator set
+pub struct CurrentValidatorSetId<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Config> Get<sp_consensus_beefy_etf::ValidatorSetId> for CurrentValidatorSetId<T> {
+	fn get() -> sp_consensus_beefy_etf::ValidatorSetId {
+		ValidatorSetId::<T>::get()
+	}
+}
+
 impl<T: Config> Pallet<T> {
 
 	/// Return the current validator set id
@@ -92,8 +92,14 @@ parameter_types! {
 }
 
 impl pallet_etf::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
 	type BeefyId = BeefyId;
 	type MaxAuthorities = ConstU32<100>;
+	type ShareVerifier = ();
+	type UpdateOrigin = frame_system::EnsureRoot<u64>;
+	type ValidatorSet = Session;
+	type BeefyValidatorSetId = pallet_beefy::CurrentValidatorSetId<Test>;
+	type WeightInfo = ();
 }
 
 impl pallet_beefy::Config for Test {
@@ -0,0 +1,104 @@
+/*
+ * Copyright 2024 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Benchmarking for pallet_encrypted_mempool.
+
+use super::*;
+use frame_benchmarking::v1::{account, benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+use sp_std::vec;
+
+use crate::Pallet as EncryptedMempool;
+
+const SEED: u32 = 0;
+
+/// A call that any signed account can dispatch, along with its weight
+fn remark<T: Config>() -> (Vec<u8>, Weight) {
+	let call: <T as Config>::RuntimeCall = frame_system::Call::<T>::remark { remark: vec![] }.into();
+	(call.encode(), call.get_dispatch_info().weight)
+}
+
+/// A funded account that holds the fee for a sealed remark to `target`
+fn sealed_remark<T: Config>(
+	who: T::AccountId,
+	target: BlockNumberFor<T>,
+) -> SealedTransactionOf<T> {
+	let (call, max_weight) = remark::<T>();
+	let ciphertext = T::BenchmarkHelper::seal(&call, target)
+		.try_into()
+		.expect("a sealed remark fits in a ciphertext");
+	T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 2u32.into());
+	let fee = EncryptedMempool::<T>::fee_for(max_weight);
+	T::Currency::reserve(&who, fee).expect("the account is funded");
+	SealedTransaction { who, ciphertext, max_weight, fee }
+}
+
+/// Fill the pool for `target` with `n` sealed remarks
+fn fill_pool<T: Config>(target: BlockNumberFor<T>, n: u32) {
+	let pool = (0..n)
+		.map(|i| sealed_remark::<T>(account("submitter", i, SEED), target))
+		.collect::<Vec<_>>();
+	if n > 0 {
+		PendingHeights::<T>::try_append(target).expect("a height can be pending");
+	}
+	Pools::<T>::insert(target, BoundedVec::truncate_from(pool));
+}
+
+benchmarks! {
+	submit {
+		let n in 0 .. T::MaxTransactionsPerHeight::get() - 1;
+		let target = T::TlockProvider::latest().saturating_add(One::one());
+		fill_pool::<T>(target, n);
+
+		let caller: T::AccountId = whitelisted_caller();
+		let tx = sealed_remark::<T>(caller.clone(), target);
+		T::Currency::unreserve(&caller, tx.fee);
+	}: _(RawOrigin::Signed(caller), target, tx.ciphertext, tx.max_weight)
+	verify {
+		assert_eq!(Pools::<T>::get(target).len() as u32, n + 1);
+	}
+
+	// The pulse for the pool is missing, so each call is dropped after a single read. This only
+	// measures the cost of the pool itself, dispatching a call is measured by `dispatch_sealed`.
+	service_pool {
+		let n in 0 .. T::MaxTransactionsPerHeight::get();
+		let target = T::TlockProvider::latest().saturating_add(One::one());
+		fill_pool::<T>(target, n);
+	}: {
+		EncryptedMempool::<T>::service_pool(target);
+	}
+	verify {
+		assert!(Pools::<T>::get(target).is_empty());
+	}
+
+	dispatch_sealed {
+		let target = T::TlockProvider::latest().saturating_add(One::one());
+		let tx = sealed_remark::<T>(account("submitter", 0, SEED), target);
+		let who = tx.who.clone();
+		T::BenchmarkHelper::publish_pulse(target);
+	}: {
+		EncryptedMempool::<T>::dispatch_sealed(target, tx);
+	}
+	verify {
+		frame_system::Pallet::<T>::assert_last_event(
+			<T as Config>::RuntimeEvent::from(Event::Dispatched { who, target, result: Ok(()) })
+				.into(),
+		);
+	}
+
+	impl_benchmark_test_suite!(EncryptedMempool, crate::mock::new_test_ext(), crate::mock::Test);
+}
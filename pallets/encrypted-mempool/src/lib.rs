@@ -0,0 +1,357 @@
+/*
+ * Copyright 2024 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Encrypted Mempool Pallet
+//!
+//! Accepts calls that are timelock encrypted to the next height of the randomness beacon. Their
+//! contents stay hidden until the beacon produces the pulse for that height, at which point every
+//! call in the pool is decrypted and dispatched in an order shuffled with the randomness of that
+//! pulse.
+//! Since neither the calls nor their order are known before the pulse, they cannot be
+//! front-run.
+//!
+//! Submitters prepay the fee for the declared weight of their call. Once the call has been
+//! dispatched, the fee for the weight it actually used is charged and the rest is refunded.
+
+pub use pallet::*;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+pub use weights::WeightInfo;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	pallet_prelude::*,
+	traits::{Currency, OnUnbalanced, ReservableCurrency},
+	weights::WeightToFee,
+};
+use frame_system::pallet_prelude::*;
+use pallet_randomness_beacon::{BeaconRandomness, TimelockEncryptionProvider, TimelockError};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{Dispatchable, One, Saturating},
+	DispatchResult, RandomNumberGenerator,
+};
+use sp_std::prelude::*;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+/// A timelock encrypted call waiting for the pulse of its target height
+#[derive(
+	CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound,
+	Encode, Decode, TypeInfo, MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(MaxCiphertextLen))]
+#[codec(mel_bound(AccountId: MaxEncodedLen, Balance: MaxEncodedLen))]
+pub struct SealedTransaction<
+	AccountId: Clone + PartialEq + Eq + core::fmt::Debug,
+	Balance: Clone + PartialEq + Eq + core::fmt::Debug,
+	MaxCiphertextLen: Get<u32>,
+> {
+	/// The account that submitted the call and that it is dispatched from
+	pub who: AccountId,
+	/// The encrypted call
+	pub ciphertext: BoundedVec<u8, MaxCiphertextLen>,
+	/// The maximum weight the decrypted call is allowed to consume
+	pub max_weight: Weight,
+	/// The fee held from `who` for `max_weight`
+	pub fee: Balance,
+}
+
+pub type SealedTransactionOf<T> = SealedTransaction<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	<T as Config>::MaxCiphertextLen,
+>;
+
+/// Helper to benchmark the decryption of sealed calls.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<BlockNumber> {
+	/// Timelock encrypt `message` to the pulse of `target`.
+	fn seal(message: &[u8], target: BlockNumber) -> Vec<u8>;
+
+	/// Make the beacon produce the pulse of `target`, so the messages sealed for it can be
+	/// decrypted.
+	fn publish_pulse(target: BlockNumber);
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The aggregated call type.
+		type RuntimeCall: Parameter
+			+ Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+			+ GetDispatchInfo
+			+ From<frame_system::Call<Self>>;
+
+		/// The currency in which fees are prepaid.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Converts the weight of a call into the fee for it.
+		type WeightToFee: WeightToFee<Balance = BalanceOf<Self>>;
+
+		/// Handler for the fees charged for dispatched calls.
+		type OnFeeCharged: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// Something that can decrypt messages locked to a height of the beacon.
+		type TlockProvider: TimelockEncryptionProvider<BlockNumberFor<Self>>;
+
+		/// The randomness of the beacon pulses, used to shuffle a pool before it is dispatched.
+		type Randomness: BeaconRandomness<Self::Hash, BlockNumberFor<Self>>;
+
+		/// The maximum length of an encrypted call.
+		#[pallet::constant]
+		type MaxCiphertextLen: Get<u32>;
+
+		/// The maximum number of calls that can be submitted for a single height.
+		#[pallet::constant]
+		type MaxTransactionsPerHeight: Get<u32>;
+
+		/// The maximum total declared weight of the calls submitted for a single height.
+		///
+		/// A pool is dispatched within a single block, so this must leave room for the rest of
+		/// the block.
+		#[pallet::constant]
+		type MaxWeightPerHeight: Get<Weight>;
+
+		/// The maximum number of heights with calls waiting for their pulse.
+		#[pallet::constant]
+		type MaxPendingHeights: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+
+		/// Helper to benchmark the decryption of sealed calls.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<BlockNumberFor<Self>>;
+	}
+
+	/// The encrypted calls submitted for each height of the beacon
+	#[pallet::storage]
+	pub type Pools<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BoundedVec<SealedTransactionOf<T>, T::MaxTransactionsPerHeight>,
+		ValueQuery,
+	>;
+
+	/// The heights with a non-empty pool, in ascending order
+	#[pallet::storage]
+	pub type PendingHeights<T: Config> =
+		StorageValue<_, BoundedVec<BlockNumberFor<T>, T::MaxPendingHeights>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An encrypted call was added to the pool for `target`.
+		Submitted { who: T::AccountId, target: BlockNumberFor<T>, index: u32 },
+		/// An encrypted call was decrypted and dispatched.
+		Dispatched { who: T::AccountId, target: BlockNumberFor<T>, result: DispatchResult },
+		/// An encrypted call could not be recovered, so it was dropped.
+		DecryptionFailed { who: T::AccountId, target: BlockNumberFor<T>, error: TimelockError },
+		/// A decrypted call exceeds its declared weight, so it was dropped.
+		Overweight {
+			who: T::AccountId,
+			target: BlockNumberFor<T>,
+			max_weight: Weight,
+			call_weight: Weight,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Calls can only be submitted for the next height of the beacon.
+		NotNextHeight,
+		/// The pool for the height is full.
+		PoolFull,
+		/// The declared weight of the pool for the height would exceed the maximum.
+		PoolOverweight,
+		/// There are too many heights waiting for their pulse.
+		TooManyPendingHeights,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			let latest = T::TlockProvider::latest();
+			let mut heights = PendingHeights::<T>::get();
+			// a single pool is dispatched per block, so the weight stays within
+			// `MaxWeightPerHeight` even if the beacon skips ahead
+			// the pending heights and the height of the beacon
+			let weight = T::DbWeight::get().reads(2);
+			match heights.first() {
+				Some(&target) if target <= latest => {
+					heights.remove(0);
+					PendingHeights::<T>::put(heights);
+					weight
+						.saturating_add(T::DbWeight::get().writes(1))
+						.saturating_add(Self::service_pool(target))
+				},
+				_ => weight,
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Submit a call that is timelock encrypted to the next height of the beacon.
+		///
+		/// The fee for `max_weight` is held until the call has been dispatched.
+		///
+		/// * `target`: The next height of the beacon, which the call is encrypted to
+		/// * `ciphertext`: The encrypted call
+		/// * `max_weight`: The maximum weight the decrypted call is allowed to consume
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::submit(T::MaxTransactionsPerHeight::get()))]
+		pub fn submit(
+			origin: OriginFor<T>,
+			target: BlockNumberFor<T>,
+			ciphertext: BoundedVec<u8, T::MaxCiphertextLen>,
+			max_weight: Weight,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let next = T::TlockProvider::latest().saturating_add(One::one());
+			ensure!(target == next, Error::<T>::NotNextHeight);
+
+			let mut pool = Pools::<T>::get(target);
+			let pool_weight =
+				pool.iter().fold(max_weight, |weight, tx| weight.saturating_add(tx.max_weight));
+			ensure!(
+				pool_weight.all_lte(T::MaxWeightPerHeight::get()),
+				Error::<T>::PoolOverweight
+			);
+
+			let fee = Self::fee_for(max_weight);
+			let index = pool.len() as u32;
+			pool.try_push(SealedTransaction { who: who.clone(), ciphertext, max_weight, fee })
+				.map_err(|_| Error::<T>::PoolFull)?;
+			if index == 0 {
+				PendingHeights::<T>::try_append(target)
+					.map_err(|_| Error::<T>::TooManyPendingHeights)?;
+			}
+			T::Currency::reserve(&who, fee)?;
+			Pools::<T>::insert(target, pool);
+
+			Self::deposit_event(Event::Submitted { who, target, index });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The fee held for a call with the given weight, including its decryption
+	fn fee_for(weight: Weight) -> BalanceOf<T> {
+		T::WeightToFee::weight_to_fee(&weight.saturating_add(T::WeightInfo::dispatch_sealed()))
+	}
+
+	/// Decrypt and dispatch the pool for `target` in a randomized order
+	fn service_pool(target: BlockNumberFor<T>) -> Weight {
+		let pool = Pools::<T>::take(target);
+		let mut weight = T::WeightInfo::service_pool(pool.len() as u32);
+
+		// the order is drawn from the pulse that unlocks the pool, not the latest one. If the
+		// beacon skipped `target` none of the calls can be decrypted, so the order is moot
+		let seed = T::Randomness::random_for(b"encrypted-mempool", target).unwrap_or_default();
+		for index in Self::shuffle(pool.len(), seed) {
+			weight.saturating_accrue(Self::dispatch_sealed(target, pool[index].clone()));
+		}
+		weight
+	}
+
+	/// A Fisher-Yates shuffle of the indices of a pool of `len` calls
+	fn shuffle(len: usize, seed: T::Hash) -> Vec<usize> {
+		let mut order: Vec<usize> = (0..len).collect();
+		let mut rng = RandomNumberGenerator::<T::Hashing>::new(seed);
+		for i in (1..len).rev() {
+			order.swap(i, rng.pick_usize(i));
+		}
+		order
+	}
+
+	/// Decrypt and dispatch a single call, settling its fee
+	fn dispatch_sealed(target: BlockNumberFor<T>, tx: SealedTransactionOf<T>) -> Weight {
+		let SealedTransaction { who, ciphertext, max_weight, fee } = tx;
+		let base_weight = T::WeightInfo::dispatch_sealed();
+
+		let maybe_call = T::TlockProvider::decrypt_at(&ciphertext, target).and_then(|bare| {
+			<T as Config>::RuntimeCall::decode(&mut bare.message.as_slice())
+				.map_err(|_| TimelockError::CallDecodeFailure)
+		});
+		let call = match maybe_call {
+			Ok(call) => call,
+			Err(error) => {
+				if error == TimelockError::MissingPulse {
+					// the beacon skipped the target height, which is not the submitter's fault
+					T::Currency::unreserve(&who, fee);
+				} else {
+					Self::charge(&who, fee, fee);
+				}
+				Self::deposit_event(Event::DecryptionFailed { who, target, error });
+				return base_weight
+			},
+		};
+
+		let call_weight = call.get_dispatch_info().weight;
+		if call_weight.any_gt(max_weight) {
+			Self::charge(&who, fee, fee);
+			Self::deposit_event(Event::Overweight { who, target, max_weight, call_weight });
+			return base_weight
+		}
+
+		let origin: T::RuntimeOrigin = frame_system::RawOrigin::Signed(who.clone()).into();
+		let (maybe_actual_weight, result) = match call.dispatch(origin) {
+			Ok(post_info) => (post_info.actual_weight, Ok(())),
+			Err(error_and_info) =>
+				(error_and_info.post_info.actual_weight, Err(error_and_info.error)),
+		};
+		let used_weight = maybe_actual_weight.unwrap_or(call_weight).min(call_weight);
+		Self::charge(&who, fee, Self::fee_for(used_weight));
+
+		Self::deposit_event(Event::Dispatched { who, target, result });
+		base_weight.saturating_add(used_weight)
+	}
+
+	/// Charge `amount` out of the `held` fee and refund the rest
+	fn charge(who: &T::AccountId, held: BalanceOf<T>, amount: BalanceOf<T>) {
+		let amount = amount.min(held);
+		let (imbalance, _) = T::Currency::slash_reserved(who, amount);
+		T::OnFeeCharged::on_unbalanced(imbalance);
+		T::Currency::unreserve(who, held.saturating_sub(amount));
+	}
+}
@@ -0,0 +1,166 @@
+/*
+ * Copyright 2024 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::*;
+
+use etf_crypto_primitives::encryption::tlock::DecryptionResult;
+use frame_support::{
+	construct_runtime, derive_impl, parameter_types,
+	traits::{ConstU128, ConstU32},
+};
+use sp_core::H256;
+use sp_io::TestExternalities;
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash},
+	BuildStorage,
+};
+
+use crate as pallet_encrypted_mempool;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		EncryptedMempool: pallet_encrypted_mempool,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u128>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u128;
+	type DustRemoval = ();
+	type RuntimeEvent = RuntimeEvent;
+	type ExistentialDeposit = ConstU128<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type RuntimeHoldReason = ();
+	type RuntimeFreezeReason = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = ();
+}
+
+parameter_types! {
+	/// The latest height of the mock beacon
+	pub static Latest: u64 = 0;
+	/// Heights the mock beacon produced no pulse for
+	pub static SkippedHeights: Vec<u64> = vec![];
+	pub MaxWeightPerHeight: Weight = Weight::from_parts(10_000_000_000, 1_000_000);
+}
+
+/// A beacon whose 'ciphertexts' are the plaintexts themselves
+pub struct MockTlockProvider;
+impl TimelockEncryptionProvider<u64> for MockTlockProvider {
+	fn decrypt_at(bytes: &[u8], when: u64) -> Result<DecryptionResult, TimelockError> {
+		if when > Latest::get() || SkippedHeights::get().contains(&when) {
+			return Err(TimelockError::MissingPulse)
+		}
+		Ok(DecryptionResult { message: bytes.to_vec(), secret: [0; 32] })
+	}
+
+	fn latest() -> u64 {
+		Latest::get()
+	}
+}
+
+pub struct MockRandomness;
+impl BeaconRandomness<H256, u64> for MockRandomness {
+	fn random_for(subject: &[u8], when: u64) -> Option<H256> {
+		if when > Latest::get() || SkippedHeights::get().contains(&when) {
+			return None
+		}
+		Some(BlakeTwo256::hash_of(&(subject, when)))
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl BenchmarkHelper<u64> for MockBenchmarkHelper {
+	fn seal(message: &[u8], _target: u64) -> Vec<u8> {
+		message.to_vec()
+	}
+
+	fn publish_pulse(target: u64) {
+		Latest::set(Latest::get().max(target));
+	}
+}
+
+/// Charges one unit per unit of ref time
+pub struct RefTimeToFee;
+impl WeightToFee for RefTimeToFee {
+	type Balance = u128;
+
+	fn weight_to_fee(weight: &Weight) -> u128 {
+		weight.ref_time() as u128
+	}
+}
+
+impl pallet_encrypted_mempool::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type WeightToFee = RefTimeToFee;
+	type OnFeeCharged = ();
+	type TlockProvider = MockTlockProvider;
+	type Randomness = MockRandomness;
+	type MaxCiphertextLen = ConstU32<1024>;
+	type MaxTransactionsPerHeight = ConstU32<4>;
+	type MaxWeightPerHeight = MaxWeightPerHeight;
+	type MaxPendingHeights = ConstU32<2>;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockBenchmarkHelper;
+}
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const INITIAL_BALANCE: u128 = 100_000_000_000;
+
+pub fn new_test_ext() -> TestExternalities {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(ALICE, INITIAL_BALANCE), (BOB, INITIAL_BALANCE), (CHARLIE, INITIAL_BALANCE)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext = TestExternalities::new(t);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		Latest::set(0);
+		SkippedHeights::set(vec![]);
+	});
+	ext
+}
+
+/// Advance the beacon to `height` and service the mempool
+pub fn run_to_height(height: u64) {
+	Latest::set(height);
+	System::set_block_number(System::block_number() + 1);
+	EncryptedMempool::on_initialize(System::block_number());
+}
@@ -0,0 +1,299 @@
+/*
+ * Copyright 2024 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::*;
+use crate::mock::*;
+use frame_support::{assert_noop, assert_ok};
+use sp_core::H256;
+
+fn remark(remark: &[u8]) -> RuntimeCall {
+	RuntimeCall::System(frame_system::Call::remark_with_event { remark: remark.to_vec() })
+}
+
+fn seal(call: &RuntimeCall) -> BoundedVec<u8, ConstU32<1024>> {
+	BoundedVec::truncate_from(call.encode())
+}
+
+fn weight_of(call: &RuntimeCall) -> Weight {
+	call.get_dispatch_info().weight
+}
+
+fn dispatched_remarks() -> Vec<u64> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::System(frame_system::Event::Remarked { sender, .. }) => Some(sender),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn submit_holds_the_fee_for_the_declared_weight() {
+	new_test_ext().execute_with(|| {
+		let call = remark(b"hello");
+		let max_weight = weight_of(&call);
+		assert_ok!(EncryptedMempool::submit(
+			RuntimeOrigin::signed(ALICE),
+			1,
+			seal(&call),
+			max_weight,
+		));
+
+		let fee = EncryptedMempool::fee_for(max_weight);
+		assert_eq!(Balances::reserved_balance(ALICE), fee);
+		assert_eq!(Pools::<Test>::get(1).len(), 1);
+		assert_eq!(PendingHeights::<Test>::get().into_inner(), vec![1]);
+		System::assert_last_event(Event::Submitted { who: ALICE, target: 1, index: 0 }.into());
+	});
+}
+
+#[test]
+fn submit_only_accepts_the_next_height() {
+	new_test_ext().execute_with(|| {
+		let call = remark(b"hello");
+		for target in [0, 2] {
+			assert_noop!(
+				EncryptedMempool::submit(
+					RuntimeOrigin::signed(ALICE),
+					target,
+					seal(&call),
+					weight_of(&call),
+				),
+				Error::<Test>::NotNextHeight,
+			);
+		}
+	});
+}
+
+#[test]
+fn submit_enforces_the_capacity_of_a_height() {
+	new_test_ext().execute_with(|| {
+		let call = remark(b"hello");
+		for _ in 0..4 {
+			assert_ok!(EncryptedMempool::submit(
+				RuntimeOrigin::signed(ALICE),
+				1,
+				seal(&call),
+				weight_of(&call),
+			));
+		}
+		assert_noop!(
+			EncryptedMempool::submit(RuntimeOrigin::signed(BOB), 1, seal(&call), weight_of(&call)),
+			Error::<Test>::PoolFull,
+		);
+
+		run_to_height(1);
+		assert_noop!(
+			EncryptedMempool::submit(
+				RuntimeOrigin::signed(BOB),
+				2,
+				seal(&call),
+				MaxWeightPerHeight::get().saturating_add(Weight::from_parts(1, 0)),
+			),
+			Error::<Test>::PoolOverweight,
+		);
+	});
+}
+
+#[test]
+fn submit_enforces_the_number_of_pending_heights() {
+	new_test_ext().execute_with(|| {
+		let call = remark(b"hello");
+		for height in 0..2 {
+			// the beacon moves on while the pools are left pending
+			Latest::set(height);
+			assert_ok!(EncryptedMempool::submit(
+				RuntimeOrigin::signed(ALICE),
+				height + 1,
+				seal(&call),
+				weight_of(&call),
+			));
+		}
+		Latest::set(2);
+		assert_noop!(
+			EncryptedMempool::submit(RuntimeOrigin::signed(ALICE), 3, seal(&call), weight_of(&call)),
+			Error::<Test>::TooManyPendingHeights,
+		);
+	});
+}
+
+#[test]
+fn pool_is_dispatched_and_unused_weight_refunded() {
+	new_test_ext().execute_with(|| {
+		let call = remark(b"hello");
+		let call_weight = weight_of(&call);
+		let max_weight = call_weight.saturating_mul(2);
+		assert_ok!(EncryptedMempool::submit(
+			RuntimeOrigin::signed(ALICE),
+			1,
+			seal(&call),
+			max_weight,
+		));
+
+		// nothing happens before the pulse
+		EncryptedMempool::on_initialize(System::block_number());
+		assert_eq!(Pools::<Test>::get(1).len(), 1);
+
+		run_to_height(1);
+		assert!(Pools::<Test>::get(1).is_empty());
+		assert!(PendingHeights::<Test>::get().is_empty());
+		assert_eq!(dispatched_remarks(), vec![ALICE]);
+		System::assert_last_event(Event::Dispatched { who: ALICE, target: 1, result: Ok(()) }.into());
+
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(
+			Balances::free_balance(ALICE),
+			INITIAL_BALANCE - EncryptedMempool::fee_for(call_weight),
+		);
+	});
+}
+
+#[test]
+fn pool_is_dispatched_in_a_shuffled_order() {
+	new_test_ext().execute_with(|| {
+		let call = remark(b"hello");
+		let submitters = vec![ALICE, BOB, CHARLIE, ALICE];
+		for who in submitters.iter() {
+			assert_ok!(EncryptedMempool::submit(
+				RuntimeOrigin::signed(*who),
+				1,
+				seal(&call),
+				weight_of(&call),
+			));
+		}
+
+		run_to_height(1);
+		let seed = MockRandomness::random_for(b"encrypted-mempool", 1).unwrap();
+		let order = EncryptedMempool::shuffle(submitters.len(), seed);
+		let expected: Vec<u64> = order.iter().map(|index| submitters[*index]).collect();
+		assert_eq!(dispatched_remarks(), expected);
+	});
+}
+
+#[test]
+fn pool_is_shuffled_with_the_pulse_of_its_height() {
+	new_test_ext().execute_with(|| {
+		let call = remark(b"hello");
+		let submitters = vec![ALICE, BOB, CHARLIE, ALICE];
+		for who in submitters.iter() {
+			assert_ok!(EncryptedMempool::submit(
+				RuntimeOrigin::signed(*who),
+				1,
+				seal(&call),
+				weight_of(&call),
+			));
+		}
+
+		// the beacon is already past the height when the pool is dispatched
+		run_to_height(3);
+		let seed = MockRandomness::random_for(b"encrypted-mempool", 1).unwrap();
+		assert_ne!(Some(seed), MockRandomness::random_for(b"encrypted-mempool", 3));
+		let order = EncryptedMempool::shuffle(submitters.len(), seed);
+		let expected: Vec<u64> = order.iter().map(|index| submitters[*index]).collect();
+		assert_eq!(dispatched_remarks(), expected);
+	});
+}
+
+#[test]
+fn shuffle_is_a_deterministic_permutation() {
+	new_test_ext().execute_with(|| {
+		let seed = H256::repeat_byte(7);
+		let order = EncryptedMempool::shuffle(16, seed);
+		assert_eq!(order, EncryptedMempool::shuffle(16, seed));
+
+		let mut sorted = order.clone();
+		sorted.sort();
+		assert_eq!(sorted, (0..16).collect::<Vec<_>>());
+		assert_ne!(order, EncryptedMempool::shuffle(16, H256::repeat_byte(8)));
+	});
+}
+
+#[test]
+fn undecodable_call_is_charged_the_full_fee() {
+	new_test_ext().execute_with(|| {
+		let max_weight = weight_of(&remark(b"hello"));
+		assert_ok!(EncryptedMempool::submit(
+			RuntimeOrigin::signed(ALICE),
+			1,
+			BoundedVec::truncate_from(vec![255, 255, 255]),
+			max_weight,
+		));
+
+		run_to_height(1);
+		System::assert_last_event(
+			Event::DecryptionFailed {
+				who: ALICE,
+				target: 1,
+				error: TimelockError::CallDecodeFailure,
+			}
+			.into(),
+		);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(
+			Balances::free_balance(ALICE),
+			INITIAL_BALANCE - EncryptedMempool::fee_for(max_weight),
+		);
+	});
+}
+
+#[test]
+fn overweight_call_is_charged_the_full_fee() {
+	new_test_ext().execute_with(|| {
+		let call = remark(b"hello");
+		let max_weight = Weight::from_parts(1, 0);
+		assert_ok!(EncryptedMempool::submit(
+			RuntimeOrigin::signed(ALICE),
+			1,
+			seal(&call),
+			max_weight,
+		));
+
+		run_to_height(1);
+		assert!(dispatched_remarks().is_empty());
+		System::assert_last_event(
+			Event::Overweight { who: ALICE, target: 1, max_weight, call_weight: weight_of(&call) }
+				.into(),
+		);
+		assert_eq!(
+			Balances::free_balance(ALICE),
+			INITIAL_BALANCE - EncryptedMempool::fee_for(max_weight),
+		);
+	});
+}
+
+#[test]
+fn skipped_height_is_refunded_in_full() {
+	new_test_ext().execute_with(|| {
+		let call = remark(b"hello");
+		assert_ok!(EncryptedMempool::submit(
+			RuntimeOrigin::signed(ALICE),
+			1,
+			seal(&call),
+			weight_of(&call),
+		));
+
+		SkippedHeights::set(vec![1]);
+		run_to_height(2);
+		assert!(dispatched_remarks().is_empty());
+		System::assert_last_event(
+			Event::DecryptionFailed { who: ALICE, target: 1, error: TimelockError::MissingPulse }
+				.into(),
+		);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE);
+	});
+}
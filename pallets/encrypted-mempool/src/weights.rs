@@ -0,0 +1,98 @@
+/*
+ * Copyright 2024 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Weights for pallet_encrypted_mempool
+//!
+//! These weights are conservative estimates until the benchmarks in `benchmarking.rs` have
+//! been run on reference hardware, with:
+//!
+//! ```sh
+//! ./target/release/node benchmark pallet --chain dev --wasm-execution=compiled \
+//!     --pallet pallet_encrypted_mempool --extrinsic '*' --steps 50 --repeat 20 \
+//!     --output pallets/encrypted-mempool/src/weights.rs
+//! ```
+//!
+//! Dispatching a sealed call is dominated by its timelock decryption, which requires a pairing.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_encrypted_mempool.
+pub trait WeightInfo {
+	fn submit(n: u32, ) -> Weight;
+	fn service_pool(n: u32, ) -> Weight;
+	fn dispatch_sealed() -> Weight;
+}
+
+/// Weights for pallet_encrypted_mempool using the Substrate node and recommended hardware.
+pub struct SubstrateWeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeightInfo<T> {
+	/// Storage: `EncryptedMempool::Pools` (r:1 w:1)
+	/// Storage: `EncryptedMempool::PendingHeights` (r:1 w:1)
+	/// Storage: `RandomnessBeacon::Height` (r:1 w:0)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// The range of component `n` is `[0, 256]`.
+	fn submit(n: u32, ) -> Weight {
+		Weight::from_parts(25_000_000, 0)
+			.saturating_add(Weight::from_parts(150_000, 4_200).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Storage: `EncryptedMempool::Pools` (r:1 w:1)
+	/// Storage: `RandomnessBeacon::Pulses` (r:1 w:0)
+	/// The range of component `n` is `[0, 256]`.
+	fn service_pool(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(Weight::from_parts(200_000, 4_200).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `RandomnessBeacon::Pulses` (r:1 w:0)
+	/// Storage: `Etf::CurrentEpoch` (r:1 w:0)
+	/// Storage: `Etf::RoundKeys` (r:1 w:0)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn dispatch_sealed() -> Weight {
+		Weight::from_parts(450_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn submit(n: u32, ) -> Weight {
+		Weight::from_parts(25_000_000, 0)
+			.saturating_add(Weight::from_parts(150_000, 4_200).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn service_pool(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(Weight::from_parts(200_000, 4_200).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn dispatch_sealed() -> Weight {
+		Weight::from_parts(450_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
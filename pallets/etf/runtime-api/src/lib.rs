@@ -0,0 +1,43 @@
+/*
+ * Copyright 2024 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Runtime API definition for the ETF pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// API to query the round public keys needed for timelock encryption
+	pub trait EtfApi<BlockNumber> where
+		BlockNumber: Codec,
+	{
+		/// Return the epoch and serialized round public key that a ciphertext for
+		/// `block_number` should be encrypted to, if it is known
+		fn round_pubkey_for(block_number: BlockNumber) -> Option<(u32, Vec<u8>)>;
+
+		/// Return the serialized round public key that was active during `epoch`
+		fn round_pubkey_at(epoch: u32) -> Option<Vec<u8>>;
+
+		/// Return the epoch of the current round public key
+		fn current_epoch() -> u32;
+
+		/// Return the id of the BEEFY validator set that signs with the round public key of
+		/// `epoch`, which is part of the identity a ciphertext is encrypted to
+		fn validator_set_id_at(epoch: u32) -> Option<u64>;
+	}
+}
@@ -19,27 +19,64 @@ use codec::MaxEncodedLen;
 
 use frame_support::{
 	pallet_prelude::*,
-	traits::Get,
+	traits::{Get, ValidatorSet},
 	BoundedVec, Parameter,
 };
-use sp_runtime::traits::Member;
-use sp_std::prelude::*;
+use frame_system::pallet_prelude::*;
+use sp_runtime::{traits::Member, RuntimeDebug};
+use sp_staking::SessionIndex;
+use sp_std::{marker::PhantomData, prelude::*};
 
-use sp_consensus_beefy_etf::BeefyAuthorityId;
+use ark_serialize::CanonicalDeserialize;
+use etf_crypto_primitives::proofs::hashed_el_gamal_sigma::BatchPoK;
+use sp_consensus_beefy_etf::{BeefyAuthorityId, ValidatorSetId};
+use w3f_bls::{DoublePublicKey, EngineBLS};
 
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+pub use weights::WeightInfo;
 
 pub use pallet::*;
 
+/// A serialized round public key
+pub type RoundPublicKey = BoundedVec<u8, ConstU32<144>>;
+
+/// A serialized proof of knowledge of a share
+pub type Share = BoundedVec<u8, ConstU32<1024>>;
+
+/// Counts the round public keys, starting from the genesis key at epoch 0
+pub type Epoch = u32;
+
+/// A round public key along with a resharing of its secret, waiting to be activated
+#[derive(
+	CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound,
+	Encode, Decode, TypeInfo, MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(MaxAuthorities))]
+#[codec(mel_bound(BeefyId: MaxEncodedLen))]
+pub struct StagedRound<BeefyId: Clone + PartialEq + Eq + core::fmt::Debug, MaxAuthorities: Get<u32>> {
+	/// the session index at (or after) which the round becomes active
+	pub activate_at: SessionIndex,
+	/// the new round public key
+	pub round_pubkey: RoundPublicKey,
+	/// the new shares, in the same order as the commitments
+	pub shares: BoundedVec<Share, MaxAuthorities>,
+	/// the new commitments
+	pub commitments: BoundedVec<BeefyId, MaxAuthorities>,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
 		/// Authority identifier type
 		type BeefyId: Member
 			+ Parameter
@@ -52,9 +89,20 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxAuthorities: Get<u32>;
 
-		// TODO
-		// /// Weights for this pallet.
-		// type WeightInfo: WeightInfo;
+		/// Something that can verify the proofs of knowledge of each share in a resharing
+		type ShareVerifier: ShareVerifier<Self::BeefyId>;
+
+		/// The origin allowed to stage a new round public key and resharing
+		type UpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Something that knows the current session index
+		type ValidatorSet: ValidatorSet<Self::AccountId>;
+
+		/// The id of the current BEEFY validator set, which signs with the round public key
+		type BeefyValidatorSetId: Get<ValidatorSetId>;
+
+		/// Weights for this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::pallet]
@@ -63,7 +111,7 @@ pub mod pallet {
 	/// publicly verifiable shares for the current round (a resharing)
 	#[pallet::storage]
 	pub type Shares<T: Config> = 
-		StorageValue<_, BoundedVec<BoundedVec<u8, ConstU32<1024>>, T::MaxAuthorities>, ValueQuery>;
+		StorageValue<_, BoundedVec<Share, T::MaxAuthorities>, ValueQuery>;
 
 	/// public commitments of the the expected validator to etf pubkey
 	/// assumes order follows the same as the Authorities StorageValue 
@@ -74,7 +122,44 @@ pub mod pallet {
 	/// the public key for the round (or rounds)
 	#[pallet::storage]
 	pub type RoundPublic<T: Config> = 
-		StorageValue<_, BoundedVec<u8, ConstU32<144>>, ValueQuery>;
+		StorageValue<_, RoundPublicKey, ValueQuery>;
+
+	/// the epoch of the current round public key
+	#[pallet::storage]
+	pub type CurrentEpoch<T: Config> = StorageValue<_, Epoch, ValueQuery>;
+
+	/// every round public key that has been active, indexed by epoch
+	/// old keys are kept so that ciphertexts encrypted under them stay decryptable
+	#[pallet::storage]
+	pub type RoundKeys<T: Config> =
+		StorageMap<_, Twox64Concat, Epoch, RoundPublicKey, OptionQuery>;
+
+	/// the commitments of the validators that held shares of each epoch's round public key
+	/// kept so that late pulses for blocks of a previous epoch can still be verified
+	#[pallet::storage]
+	pub type RoundCommitments<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		Epoch,
+		BoundedVec<T::BeefyId, T::MaxAuthorities>,
+		OptionQuery,
+	>;
+
+	/// the id of the BEEFY validator set that signs with each epoch's round public key,
+	/// recorded when the key becomes active
+	#[pallet::storage]
+	pub type RoundValidatorSetIds<T: Config> =
+		StorageMap<_, Twox64Concat, Epoch, ValidatorSetId, OptionQuery>;
+
+	/// the block number at which each epoch's round public key became active
+	#[pallet::storage]
+	pub type EpochStart<T: Config> =
+		StorageMap<_, Twox64Concat, Epoch, BlockNumberFor<T>, OptionQuery>;
+
+	/// a round public key and resharing waiting to be activated at a session boundary
+	#[pallet::storage]
+	pub type Staged<T: Config> =
+		StorageValue<_, StagedRound<T::BeefyId, T::MaxAuthorities>, OptionQuery>;
 
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
@@ -105,12 +190,98 @@ pub mod pallet {
 
 	#[pallet::error]
 	pub enum Error<T> {
-		TODO,
+		/// The round public key is not a well-formed (compressed) double public key
+		InvalidRoundPublic,
+		/// There are more shares than the maximum number of authorities
+		TooManyShares,
+		/// A serialized proof of knowledge exceeds the maximum size
+		ShareTooLarge,
+		/// A commitment could not be interpreted as a public key
+		InvalidCommitment,
+		/// A proof of knowledge could not be deserialized
+		MalformedShare,
+		/// A proof of knowledge does not verify against its commitment and the round public key
+		InvalidShareProof,
+		/// The activation session has already started
+		SessionInPast,
+		/// There is no staged round
+		NothingStaged,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A new round public key and resharing was staged
+		RoundStaged { epoch: Epoch, activate_at: SessionIndex },
+		/// The staged round was discarded before activation
+		StagedRoundCanceled { epoch: Epoch },
+		/// A new round public key, shares, and commitments are now active
+		RoundActivated { epoch: Epoch, session: SessionIndex },
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			if let Some(staged) = Staged::<T>::get() {
+				weight.saturating_accrue(T::DbWeight::get().reads(1));
+				let session = T::ValidatorSet::session_index();
+				if session >= staged.activate_at {
+					Self::activate(staged, session);
+					weight.saturating_accrue(T::WeightInfo::activate_round());
+				}
+			}
+			weight
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// Stage a new round public key along with a resharing of its secret.
+		/// The round replaces the active round public key, shares, and commitments
+		/// at the first block of session `activate_at`. Any previously staged round
+		/// is replaced.
+		///
+		/// * `round_pubkey`: The serialized round public key
+		/// * `resharing`: The commitment and serialized proof of knowledge of each share
+		/// * `activate_at`: The session in which the round becomes active
+		///
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::stage_round(resharing.len() as u32))]
+		pub fn stage_round(
+			origin: OriginFor<T>,
+			round_pubkey: Vec<u8>,
+			resharing: Vec<(T::BeefyId, Vec<u8>)>,
+			activate_at: SessionIndex,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				activate_at > T::ValidatorSet::session_index(),
+				Error::<T>::SessionInPast
+			);
+
+			let (round_pubkey, shares, commitments) =
+				Self::verify_resharing(round_pubkey, &resharing)?;
 
+			Staged::<T>::put(StagedRound { activate_at, round_pubkey, shares, commitments });
+			Self::deposit_event(Event::RoundStaged {
+				epoch: CurrentEpoch::<T>::get().saturating_add(1),
+				activate_at,
+			});
+			Ok(())
+		}
+
+		/// Discard the staged round before it is activated
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::cancel_staged_round())]
+		pub fn cancel_staged_round(origin: OriginFor<T>) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			Staged::<T>::take().ok_or(Error::<T>::NothingStaged)?;
+			Self::deposit_event(Event::StagedRoundCanceled {
+				epoch: CurrentEpoch::<T>::get().saturating_add(1),
+			});
+			Ok(())
+		}
 	}
 
 }
@@ -120,42 +291,157 @@ impl<T: Config> Pallet<T> {
 	fn initialize(
 		genesis_resharing: &Vec<(T::BeefyId, Vec<u8>)>,
 		round_key: Vec<u8>,
-	) -> Result<(), ()>  {
-		let bounded_rk =
-			BoundedVec::<u8, ConstU32<144>>::try_from(round_key)
-				.expect("The serialized round key should be 144 bytes.");
+	) -> Result<(), Error<T>>  {
+		let (bounded_rk, bounded_shares, bounded_commitments) =
+			Self::verify_resharing(round_key, genesis_resharing)?;
+
+		<RoundKeys<T>>::insert(0, bounded_rk.clone());
+		<RoundCommitments<T>>::insert(0, bounded_commitments.clone());
+		<EpochStart<T>>::insert(0, <frame_system::Pallet<T>>::block_number());
+		<RoundValidatorSetIds<T>>::insert(0, T::BeefyValidatorSetId::get());
 		<RoundPublic<T>>::put(bounded_rk);
+		<Shares<T>>::put(bounded_shares);
+		Commitments::<T>::put(bounded_commitments);
+		Ok(())
+	}
 
-		let mut unbounded_shares: Vec<BoundedVec<u8, ConstU32<1024>>> = Vec::new();
-		
-		genesis_resharing.iter().for_each(|(_commitment, pok_bytes)| {
-			let bounded_pok =
-				BoundedVec::<u8, ConstU32<1024>>::try_from(pok_bytes.clone())
-					.expect("genesis poks should be well formatted");
+	/// make a staged round the active one, keeping the previous round public key
+	fn activate(
+		staged: StagedRound<T::BeefyId, T::MaxAuthorities>,
+		session: SessionIndex,
+	) {
+		let epoch = CurrentEpoch::<T>::get().saturating_add(1);
+		<RoundKeys<T>>::insert(epoch, staged.round_pubkey.clone());
+		<RoundCommitments<T>>::insert(epoch, staged.commitments.clone());
+		<EpochStart<T>>::insert(epoch, <frame_system::Pallet<T>>::block_number());
+		<RoundValidatorSetIds<T>>::insert(epoch, T::BeefyValidatorSetId::get());
+		<RoundPublic<T>>::put(staged.round_pubkey);
+		<Shares<T>>::put(staged.shares);
+		Commitments::<T>::put(staged.commitments);
+		CurrentEpoch::<T>::put(epoch);
+		Staged::<T>::kill();
+		Self::deposit_event(Event::RoundActivated { epoch, session });
+	}
+
+	/// Verify a round public key and a resharing of its secret, where each entry of the
+	/// resharing is a commitment and the serialized proof of knowledge of the share behind it.
+	///
+	/// Returns the bounded round key, shares, and commitments, in the order given, if
+	/// every proof of knowledge is valid.
+	pub(crate) fn verify_resharing(
+		round_key: Vec<u8>,
+		resharing: &[(T::BeefyId, Vec<u8>)],
+	) -> Result<
+		(
+			RoundPublicKey,
+			BoundedVec<Share, T::MaxAuthorities>,
+			BoundedVec<T::BeefyId, T::MaxAuthorities>,
+		),
+		Error<T>,
+	> {
+		let bounded_rk = RoundPublicKey::try_from(round_key)
+			.map_err(|_| Error::<T>::InvalidRoundPublic)?;
+		T::ShareVerifier::verify_round_key(&bounded_rk)
+			.map_err(Error::<T>::from)?;
+
+		let mut unbounded_shares: Vec<Share> = Vec::new();
+		for (commitment, pok_bytes) in resharing.iter() {
+			T::ShareVerifier::verify_share(&bounded_rk, commitment, pok_bytes)
+				.map_err(Error::<T>::from)?;
+			let bounded_pok = Share::try_from(pok_bytes.clone())
+				.map_err(|_| Error::<T>::ShareTooLarge)?;
 			unbounded_shares.push(bounded_pok);
-		});
-		
+		}
+
 		let bounded_shares =
-			BoundedVec::<BoundedVec<u8, ConstU32<1024>>, T::MaxAuthorities>::try_from(
+			BoundedVec::<Share, T::MaxAuthorities>::try_from(
 				unbounded_shares
-			).expect("There should be the correct number of genesis resharings");
-		<Shares<T>>::put(bounded_shares);
+			).map_err(|_| Error::<T>::TooManyShares)?;
 
 		let bounded_commitments =
 			BoundedVec::<T::BeefyId, T::MaxAuthorities>::try_from(
-				genesis_resharing.iter()
+				resharing.iter()
 					.map(|g| g.0.clone())
 					.collect::<Vec<_>>()
-			).map_err(|_| ())?;
+			).map_err(|_| Error::<T>::TooManyShares)?;
 
-		Commitments::<T>::put(bounded_commitments);
-		Ok(())
+		Ok((bounded_rk, bounded_shares, bounded_commitments))
 	}
 
-	pub fn round_pubkey() -> BoundedVec<u8, ConstU32<144>> {
+	pub fn round_pubkey() -> RoundPublicKey {
 		RoundPublic::<T>::get()
 	}
 
+	/// the round public key that was active during `epoch`, if any
+	pub fn round_pubkey_at(epoch: Epoch) -> Option<RoundPublicKey> {
+		RoundKeys::<T>::get(epoch)
+	}
+
+	pub fn current_epoch() -> Epoch {
+		CurrentEpoch::<T>::get()
+	}
+
+	/// the commitments of the validators that held shares of the round public key of `epoch`
+	pub fn commitments_at(epoch: Epoch) -> Option<BoundedVec<T::BeefyId, T::MaxAuthorities>> {
+		RoundCommitments::<T>::get(epoch)
+	}
+
+	/// the id of the BEEFY validator set that signs with the round public key of `epoch`
+	pub fn validator_set_id_at(epoch: Epoch) -> Option<ValidatorSetId> {
+		RoundValidatorSetIds::<T>::get(epoch)
+	}
+
+	/// the epoch whose round public key was active at `block_number`, if any
+	///
+	/// epochs start at non-decreasing blocks, so this is a binary search that reads at most
+	/// 33 `EpochStart` entries
+	pub fn epoch_at(block_number: BlockNumberFor<T>) -> Option<Epoch> {
+		let started = |epoch: Epoch| {
+			EpochStart::<T>::get(epoch).map_or(false, |start| start <= block_number)
+		};
+		if !started(0) {
+			return None
+		}
+		// the last epoch that started at or before `block_number`
+		let (mut low, mut high) = (0, CurrentEpoch::<T>::get());
+		while low < high {
+			let mid = low + (high - low + 1) / 2;
+			if started(mid) {
+				low = mid;
+			} else {
+				high = mid - 1;
+			}
+		}
+		Some(low)
+	}
+
+	/// the round public key that was active at `block_number`, along with its epoch
+	pub fn round_pubkey_at_block(
+		block_number: BlockNumberFor<T>,
+	) -> Option<(Epoch, RoundPublicKey)> {
+		let epoch = Self::epoch_at(block_number)?;
+		RoundKeys::<T>::get(epoch).map(|pk| (epoch, pk))
+	}
+
+	/// the round public key that a ciphertext for `block_number` should be encrypted to,
+	/// along with its epoch
+	///
+	/// For future blocks this is the current round public key, unless a round is staged:
+	/// the block at which it activates is not known in advance, so there is no single key
+	/// to encrypt to until it is active.
+	pub fn encryption_key_for(
+		block_number: BlockNumberFor<T>,
+	) -> Option<(Epoch, RoundPublicKey)> {
+		if block_number > <frame_system::Pallet<T>>::block_number() {
+			if Staged::<T>::exists() {
+				return None
+			}
+			let epoch = CurrentEpoch::<T>::get();
+			return RoundKeys::<T>::get(epoch).map(|pk| (epoch, pk))
+		}
+		Self::round_pubkey_at_block(block_number)
+	}
+
 	pub fn commitments() -> BoundedVec<T::BeefyId, T::MaxAuthorities> {
 		Commitments::<T>::get()
 	}
@@ -172,3 +458,88 @@ impl<T: Config> RoundCommitmentProvider<T::BeefyId, T::MaxAuthorities> for Palle
 		Commitments::<T>::get()
 	}
 }
+
+/// The reasons a resharing can fail verification
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ShareVerificationError {
+	/// the round public key could not be deserialized
+	InvalidRoundPublic,
+	/// the commitment could not be deserialized
+	InvalidCommitment,
+	/// the proof of knowledge could not be deserialized
+	MalformedProof,
+	/// the proof of knowledge is not valid
+	InvalidProof,
+}
+
+impl<T: Config> From<ShareVerificationError> for Error<T> {
+	fn from(e: ShareVerificationError) -> Self {
+		match e {
+			ShareVerificationError::InvalidRoundPublic => Error::<T>::InvalidRoundPublic,
+			ShareVerificationError::InvalidCommitment => Error::<T>::InvalidCommitment,
+			ShareVerificationError::MalformedProof => Error::<T>::MalformedShare,
+			ShareVerificationError::InvalidProof => Error::<T>::InvalidShareProof,
+		}
+	}
+}
+
+/// A type that can verify the shares of a resharing before they are made available to validators
+pub trait ShareVerifier<BeefyId> {
+	/// check that the serialized round public key is well formed
+	fn verify_round_key(round_pubkey: &[u8]) -> Result<(), ShareVerificationError>;
+
+	/// check that `pok` is a valid proof of knowledge of the share committed to by
+	/// `commitment`, dealt under the serialized round public key `round_pubkey`
+	fn verify_share(
+		round_pubkey: &[u8],
+		commitment: &BeefyId,
+		pok: &[u8],
+	) -> Result<(), ShareVerificationError>;
+}
+
+/// Accepts any resharing, only intended for testing
+impl<BeefyId> ShareVerifier<BeefyId> for () {
+	fn verify_round_key(_round_pubkey: &[u8]) -> Result<(), ShareVerificationError> {
+		Ok(())
+	}
+
+	fn verify_share(
+		_round_pubkey: &[u8],
+		_commitment: &BeefyId,
+		_pok: &[u8],
+	) -> Result<(), ShareVerificationError> {
+		Ok(())
+	}
+}
+
+/// Verifies the batch proofs of knowledge output by an ACSS resharing over the engine `E`,
+/// where commitments and the round public key are compressed `DoublePublicKey<E>`s
+pub struct AcssShareVerifier<E>(PhantomData<E>);
+
+impl<E: EngineBLS, BeefyId: Encode> ShareVerifier<BeefyId> for AcssShareVerifier<E> {
+	fn verify_round_key(round_pubkey: &[u8]) -> Result<(), ShareVerificationError> {
+		DoublePublicKey::<E>::deserialize_compressed(round_pubkey)
+			.map_err(|_| ShareVerificationError::InvalidRoundPublic)?;
+		Ok(())
+	}
+
+	fn verify_share(
+		round_pubkey: &[u8],
+		commitment: &BeefyId,
+		pok: &[u8],
+	) -> Result<(), ShareVerificationError> {
+		let rk = DoublePublicKey::<E>::deserialize_compressed(round_pubkey)
+			.map_err(|_| ShareVerificationError::InvalidRoundPublic)?;
+		let commitment_bytes = commitment.encode();
+		let pk = DoublePublicKey::<E>::deserialize_compressed(&commitment_bytes[..])
+			.map_err(|_| ShareVerificationError::InvalidCommitment)?;
+		let pok = BatchPoK::<E::PublicKeyGroup>::deserialize_compressed(pok)
+			.map_err(|_| ShareVerificationError::MalformedProof)?;
+		// the proof must open to the commitment stored for this validator
+		// and be dealt from the secret behind the round public key
+		if !pok.commitments.contains(&pk.1) || !pok.verify(rk.1) {
+			return Err(ShareVerificationError::InvalidProof)
+		}
+		Ok(())
+	}
+}
@@ -1,18 +1,19 @@
 use std::vec;
 
 use frame_support::{
-	construct_runtime, derive_impl,
-	traits::ConstU32,
+	construct_runtime, derive_impl, parameter_types,
+	traits::{ConstU32, ConstU64, ValidatorSet},
 };
 use sp_io::TestExternalities;
 use sp_runtime::{
 	app_crypto::bls381::Public,
-	traits::{OpaqueKeys},
+	traits::{ConvertInto, OpaqueKeys},
 	BuildStorage,
 };
 use sp_state_machine::BasicExternalities;
 
 use crate as pallet_etf;
+use crate::{ShareVerificationError, ShareVerifier};
 
 pub use sp_consensus_beefy_etf::{
 	bls_crypto::AuthorityId as BeefyId,
@@ -34,9 +35,58 @@ impl frame_system::Config for Test {
 }
 
 impl pallet_etf::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
 	type BeefyId = BeefyId;
 	type MaxAuthorities = ConstU32<100>;
+	type ShareVerifier = MockShareVerifier;
+	type UpdateOrigin = frame_system::EnsureRoot<u64>;
+	type ValidatorSet = MockValidatorSet;
+	type BeefyValidatorSetId = ConstU64<0>;
+	type WeightInfo = ();
 }
+
+parameter_types! {
+	pub static CurrentSession: u32 = 0;
+}
+
+/// A validator set whose session index is set by the test
+pub struct MockValidatorSet;
+impl ValidatorSet<u64> for MockValidatorSet {
+	type ValidatorId = u64;
+	type ValidatorIdOf = ConvertInto;
+
+	fn session_index() -> sp_staking::SessionIndex {
+		CurrentSession::get()
+	}
+
+	fn validators() -> Vec<u64> {
+		Vec::new()
+	}
+}
+
+/// A share verifier that rejects empty round keys and a few fixed proofs
+pub struct MockShareVerifier;
+impl ShareVerifier<BeefyId> for MockShareVerifier {
+	fn verify_round_key(round_pubkey: &[u8]) -> Result<(), ShareVerificationError> {
+		if round_pubkey.is_empty() {
+			return Err(ShareVerificationError::InvalidRoundPublic)
+		}
+		Ok(())
+	}
+
+	fn verify_share(
+		_round_pubkey: &[u8],
+		_commitment: &BeefyId,
+		pok: &[u8],
+	) -> Result<(), ShareVerificationError> {
+		match pok {
+			[0] => Err(ShareVerificationError::MalformedProof),
+			[1] => Err(ShareVerificationError::InvalidProof),
+			_ => Ok(()),
+		}
+	}
+}
+
 // Note, that we can't use `UintAuthorityId` here. Reason is that the implementation
 // of `to_public_key()` assumes, that a public key is 32 bytes long. This is true for
 // ed25519 and sr25519 but *not* for aggregatable BLS. A compressed aggregated BLS public key is 144 bytes
@@ -16,6 +16,12 @@
 // limitations under the License.
 use std::vec;
 use crate::{self as etf, mock::*, Call, Config, Error, Weight};
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
+use sp_runtime::{BuildStorage, DispatchError};
+use codec::Encode;
+use etf::{ShareVerificationError, ShareVerifier};
+use sp_consensus_beefy_etf::test_utils::etf_genesis;
+use sp_core::Pair;
 
 #[test]
 fn genesis_session_initializes_resharing_and_commitments_with_valid_values() {
@@ -36,4 +42,256 @@ fn genesis_session_initializes_resharing_and_commitments_with_valid_values() {
 		assert_eq!(resharings[1], want_resharing[1].1);
         assert_eq!(resharings[2], want_resharing[2].1);
 	});
-}
\ No newline at end of file
+}
+
+#[test]
+fn verify_resharing_accepts_valid_shares() {
+	new_test_ext(vec![1, 2]).execute_with(|| {
+		let resharing = vec![(mock_beefy_id(1), vec![2]), (mock_beefy_id(2), vec![3])];
+		let (rk, shares, commitments) =
+			etf::Pallet::<Test>::verify_resharing(vec![1], &resharing).unwrap();
+		assert_eq!(rk.to_vec(), vec![1]);
+		assert_eq!(shares.len(), 2);
+		assert_eq!(commitments[1], mock_beefy_id(2));
+	});
+}
+
+#[test]
+fn verify_resharing_rejects_invalid_round_key() {
+	new_test_ext(vec![1]).execute_with(|| {
+		let resharing = vec![(mock_beefy_id(1), vec![2])];
+		assert!(matches!(
+			etf::Pallet::<Test>::verify_resharing(vec![], &resharing),
+			Err(Error::<Test>::InvalidRoundPublic),
+		));
+		assert!(matches!(
+			etf::Pallet::<Test>::verify_resharing(vec![1; 145], &resharing),
+			Err(Error::<Test>::InvalidRoundPublic),
+		));
+	});
+}
+
+#[test]
+fn verify_resharing_rejects_invalid_shares() {
+	new_test_ext(vec![1]).execute_with(|| {
+		let malformed = vec![(mock_beefy_id(1), vec![2]), (mock_beefy_id(2), vec![0])];
+		assert!(matches!(
+			etf::Pallet::<Test>::verify_resharing(vec![1], &malformed),
+			Err(Error::<Test>::MalformedShare),
+		));
+
+		let invalid = vec![(mock_beefy_id(1), vec![1])];
+		assert!(matches!(
+			etf::Pallet::<Test>::verify_resharing(vec![1], &invalid),
+			Err(Error::<Test>::InvalidShareProof),
+		));
+
+		let too_large = vec![(mock_beefy_id(1), vec![2; 1025])];
+		assert!(matches!(
+			etf::Pallet::<Test>::verify_resharing(vec![1], &too_large),
+			Err(Error::<Test>::ShareTooLarge),
+		));
+	});
+}
+
+#[test]
+#[should_panic(expected = "The genesis resharing should be correctly derived")]
+fn genesis_rejects_invalid_shares() {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	etf::GenesisConfig::<Test> {
+		genesis_resharing: vec![(mock_beefy_id(1), vec![1])],
+		round_pubkey: vec![1],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+}
+
+fn next_session(session: u32) {
+	CurrentSession::set(session);
+	let block = System::block_number() + 1;
+	System::set_block_number(block);
+	Etf::on_initialize(block);
+}
+
+#[test]
+fn genesis_round_key_is_stored_at_epoch_zero() {
+	new_test_ext(vec![1, 2, 3]).execute_with(|| {
+		assert_eq!(etf::Pallet::<Test>::current_epoch(), 0);
+		assert_eq!(etf::Pallet::<Test>::round_pubkey_at(0), Some(etf::Pallet::<Test>::round_pubkey()));
+		assert_eq!(etf::Pallet::<Test>::round_pubkey_at(1), None);
+	});
+}
+
+#[test]
+fn root_can_stage_round_which_activates_at_session_boundary() {
+	new_test_ext(vec![1, 2, 3]).execute_with(|| {
+		System::set_block_number(1);
+		let resharing = vec![(mock_beefy_id(4), vec![4]), (mock_beefy_id(5), vec![5])];
+		assert_ok!(Etf::stage_round(RuntimeOrigin::root(), vec![7; 144], resharing, 2));
+		System::assert_last_event(etf::Event::RoundStaged { epoch: 1, activate_at: 2 }.into());
+		assert!(etf::Staged::<Test>::get().is_some());
+
+		// nothing changes before the activation session
+		next_session(1);
+		assert_eq!(etf::Pallet::<Test>::round_pubkey().to_vec(), vec![1]);
+		assert_eq!(etf::Shares::<Test>::get().len(), 3);
+
+		next_session(2);
+		System::assert_last_event(etf::Event::RoundActivated { epoch: 1, session: 2 }.into());
+		assert_eq!(etf::Pallet::<Test>::current_epoch(), 1);
+		assert_eq!(etf::Pallet::<Test>::round_pubkey().to_vec(), vec![7; 144]);
+		assert_eq!(etf::Shares::<Test>::get().to_vec(), vec![vec![4], vec![5]]);
+		assert_eq!(etf::Commitments::<Test>::get().to_vec(), vec![mock_beefy_id(4), mock_beefy_id(5)]);
+		assert!(etf::Staged::<Test>::get().is_none());
+		// the old key is kept around
+		assert_eq!(etf::Pallet::<Test>::round_pubkey_at(0).unwrap().to_vec(), vec![1]);
+		assert_eq!(etf::Pallet::<Test>::round_pubkey_at(1).unwrap().to_vec(), vec![7; 144]);
+	});
+}
+
+#[test]
+fn stage_round_requires_update_origin() {
+	new_test_ext(vec![1]).execute_with(|| {
+		let resharing = vec![(mock_beefy_id(4), vec![4])];
+		assert_noop!(
+			Etf::stage_round(RuntimeOrigin::signed(1), vec![7; 144], resharing, 2),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(Etf::cancel_staged_round(RuntimeOrigin::signed(1)), DispatchError::BadOrigin);
+	});
+}
+
+#[test]
+fn stage_round_rejects_past_sessions_and_invalid_shares() {
+	new_test_ext(vec![1]).execute_with(|| {
+		CurrentSession::set(3);
+		assert_noop!(
+			Etf::stage_round(RuntimeOrigin::root(), vec![7; 144], vec![(mock_beefy_id(4), vec![4])], 3),
+			Error::<Test>::SessionInPast,
+		);
+		assert_noop!(
+			Etf::stage_round(RuntimeOrigin::root(), vec![7; 144], vec![(mock_beefy_id(4), vec![1])], 4),
+			Error::<Test>::InvalidShareProof,
+		);
+		assert_noop!(
+			Etf::stage_round(RuntimeOrigin::root(), vec![], vec![(mock_beefy_id(4), vec![4])], 4),
+			Error::<Test>::InvalidRoundPublic,
+		);
+	});
+}
+
+#[test]
+fn staged_round_can_be_canceled() {
+	new_test_ext(vec![1]).execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(Etf::cancel_staged_round(RuntimeOrigin::root()), Error::<Test>::NothingStaged);
+		assert_ok!(Etf::stage_round(
+			RuntimeOrigin::root(),
+			vec![7; 144],
+			vec![(mock_beefy_id(4), vec![4])],
+			1,
+		));
+		assert_ok!(Etf::cancel_staged_round(RuntimeOrigin::root()));
+		System::assert_last_event(etf::Event::StagedRoundCanceled { epoch: 1 }.into());
+
+		next_session(1);
+		assert_eq!(etf::Pallet::<Test>::current_epoch(), 0);
+		assert_eq!(etf::Pallet::<Test>::round_pubkey().to_vec(), vec![1]);
+	});
+}
+
+#[test]
+fn round_keys_are_resolved_by_block_number() {
+	new_test_ext(vec![1]).execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Etf::stage_round(
+			RuntimeOrigin::root(),
+			vec![7; 144],
+			vec![(mock_beefy_id(4), vec![4])],
+			1,
+		));
+		// a key for a future block is unknown while a round is staged
+		assert_eq!(etf::Pallet::<Test>::encryption_key_for(10), None);
+
+		// activates at block 2
+		next_session(1);
+		assert_eq!(etf::Pallet::<Test>::epoch_at(0), Some(0));
+		assert_eq!(etf::Pallet::<Test>::epoch_at(1), Some(0));
+		assert_eq!(etf::Pallet::<Test>::epoch_at(2), Some(1));
+		assert_eq!(etf::Pallet::<Test>::epoch_at(100), Some(1));
+
+		let (epoch, old_key) = etf::Pallet::<Test>::round_pubkey_at_block(1).unwrap();
+		assert_eq!((epoch, old_key.to_vec()), (0, vec![1]));
+		let (epoch, new_key) = etf::Pallet::<Test>::encryption_key_for(10).unwrap();
+		assert_eq!((epoch, new_key.to_vec()), (1, vec![7; 144]));
+		let (epoch, old_key) = etf::Pallet::<Test>::encryption_key_for(1).unwrap();
+		assert_eq!((epoch, old_key.to_vec()), (0, vec![1]));
+		assert_eq!(
+			etf::Pallet::<Test>::commitments_at(1).map(|c| c.to_vec()),
+			Some(vec![mock_beefy_id(4)]),
+		);
+		assert!(etf::Pallet::<Test>::commitments_at(0).is_some());
+		assert_eq!(etf::Pallet::<Test>::commitments_at(2), None);
+	});
+}
+
+type AcssVerifier = etf::AcssShareVerifier<w3f_bls::TinyBLS377>;
+
+/// A BEEFY id backed by a real key, unlike `mock_beefy_id`, so that shares can be dealt to it
+fn beefy_id_from_seed(id: u8) -> BeefyId {
+	let kp = sp_core::bls::Pair::from_seed_slice(&[id; 32]).unwrap();
+	BeefyId::from(kp.public())
+}
+
+fn verify_acss_share(
+	round_pubkey: &[u8],
+	commitment: &BeefyId,
+	pok: &[u8],
+) -> Result<(), ShareVerificationError> {
+	<AcssVerifier as ShareVerifier<BeefyId>>::verify_share(round_pubkey, commitment, pok)
+}
+
+#[test]
+fn acss_share_verifier_accepts_dealt_shares() {
+	let (round_pubkey, resharing) =
+		etf_genesis::<w3f_bls::TinyBLS377>(vec![beefy_id_from_seed(1), beefy_id_from_seed(2)]);
+	assert_eq!(<AcssVerifier as ShareVerifier<BeefyId>>::verify_round_key(&round_pubkey), Ok(()));
+	for (commitment, pok) in resharing.iter() {
+		assert_eq!(verify_acss_share(&round_pubkey, commitment, pok), Ok(()));
+	}
+}
+
+#[test]
+fn acss_share_verifier_rejects_a_tampered_proof() {
+	let (round_pubkey, resharing) = etf_genesis::<w3f_bls::TinyBLS377>(vec![beefy_id_from_seed(1)]);
+	let (commitment, pok) = &resharing[0];
+
+	let mut tampered = pok.clone();
+	let middle = tampered.len() / 2;
+	tampered[middle] ^= 1;
+	assert!(matches!(
+		verify_acss_share(&round_pubkey, commitment, &tampered),
+		Err(ShareVerificationError::MalformedProof | ShareVerificationError::InvalidProof),
+	));
+
+	// a valid proof that was not dealt from the secret behind the round public key
+	let other_key = beefy_id_from_seed(3).encode();
+	assert_eq!(
+		verify_acss_share(&other_key, commitment, pok),
+		Err(ShareVerificationError::InvalidProof),
+	);
+	assert_eq!(
+		verify_acss_share(&round_pubkey, commitment, &pok[..pok.len() - 1]),
+		Err(ShareVerificationError::MalformedProof),
+	);
+}
+
+#[test]
+fn acss_share_verifier_rejects_a_commitment_outside_the_proof() {
+	let (round_pubkey, resharing) = etf_genesis::<w3f_bls::TinyBLS377>(vec![beefy_id_from_seed(1)]);
+	let (_, pok) = &resharing[0];
+	assert_eq!(
+		verify_acss_share(&round_pubkey, &beefy_id_from_seed(2), pok),
+		Err(ShareVerificationError::InvalidProof),
+	);
+}
@@ -0,0 +1,92 @@
+/*
+ * Copyright 2024 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Weights for pallet_etf
+//!
+//! These weights are conservative estimates and should be replaced by benchmarked values.
+//! Verifying a share is dominated by deserializing curve points and checking its proof of
+//! knowledge, which is charged per share.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_etf.
+pub trait WeightInfo {
+	fn stage_round(n: u32, ) -> Weight;
+	fn cancel_staged_round() -> Weight;
+	fn activate_round() -> Weight;
+}
+
+/// Weights for pallet_etf using the Substrate node and recommended hardware.
+pub struct SubstrateWeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeightInfo<T> {
+	/// Storage: `Etf::CurrentEpoch` (r:1 w:0)
+	/// Storage: `Etf::Staged` (r:0 w:1)
+	/// The range of component `n` is `[0, 100]`.
+	fn stage_round(n: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(Weight::from_parts(25_000_000, 1_200).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Etf::Staged` (r:1 w:1)
+	/// Storage: `Etf::CurrentEpoch` (r:1 w:0)
+	fn cancel_staged_round() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Etf::CurrentEpoch` (r:1 w:1)
+	/// Storage: `Etf::RoundKeys` (r:0 w:1)
+	/// Storage: `Etf::RoundCommitments` (r:0 w:1)
+	/// Storage: `Etf::EpochStart` (r:0 w:1)
+	/// Storage: `Beefy::ValidatorSetId` (r:1 w:0)
+	/// Storage: `Etf::RoundValidatorSetIds` (r:0 w:1)
+	/// Storage: `Etf::RoundPublic` (r:0 w:1)
+	/// Storage: `Etf::Shares` (r:0 w:1)
+	/// Storage: `Etf::Commitments` (r:0 w:1)
+	/// Storage: `Etf::Staged` (r:0 w:1)
+	fn activate_round() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(9))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn stage_round(n: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(Weight::from_parts(25_000_000, 1_200).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn cancel_staged_round() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn activate_round() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(9))
+	}
+}
@@ -0,0 +1,124 @@
+//! RPC interface for the murmur pallet.
+//!
+//! Names and MMR roots are returned as bytes.
+
+#![warn(missing_docs)]
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{ErrorObject, ErrorObjectOwned},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+
+pub use pallet_murmur_runtime_api::{MurmurApi as MurmurRuntimeApi, MurmurProxyDetails};
+
+/// The details of a murmur proxy, as returned by the RPC.
+#[derive(Clone, PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ProxyDetails<AccountId> {
+	/// The proxy account address.
+	pub address: AccountId,
+	/// The MMR root.
+	pub root: Bytes,
+	/// The number of nodes in the MMR.
+	pub size: u64,
+}
+
+impl<AccountId> From<MurmurProxyDetails<AccountId>> for ProxyDetails<AccountId> {
+	fn from(details: MurmurProxyDetails<AccountId>) -> Self {
+		Self { address: details.address, root: details.root.into_inner().into(), size: details.size }
+	}
+}
+
+/// Murmur RPC methods.
+#[rpc(client, server)]
+pub trait MurmurApi<BlockHash, AccountId, BlockNumber> {
+	/// Returns the details of the murmur proxy called `name`, if it is registered.
+	#[method(name = "murmur_proxyDetails")]
+	fn proxy_details(
+		&self,
+		name: Bytes,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<ProxyDetails<AccountId>>>;
+
+	/// Returns the name of the murmur proxy at `address`, if it is one.
+	#[method(name = "murmur_nameOf")]
+	fn name_of(&self, address: AccountId, at: Option<BlockHash>) -> RpcResult<Option<Bytes>>;
+
+	/// Returns the beacon height whose OTP is verified by calls included in the next block.
+	#[method(name = "murmur_latest")]
+	fn latest(&self, at: Option<BlockHash>) -> RpcResult<BlockNumber>;
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(err: impl std::fmt::Debug) -> ErrorObjectOwned {
+	ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query murmur wallets.",
+		Some(format!("{:?}", err)),
+	)
+}
+
+/// Provides RPC methods to look up murmur wallets.
+pub struct Murmur<C, Block> {
+	client: Arc<C>,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> Murmur<C, Block> {
+	/// Creates a new instance of the murmur RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId, BlockNumber> MurmurApiServer<Block::Hash, AccountId, BlockNumber>
+	for Murmur<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: MurmurRuntimeApi<Block, AccountId, BlockNumber>,
+	AccountId: Codec + Clone + Send + Sync + 'static + serde::Serialize + serde::de::DeserializeOwned,
+	BlockNumber: Codec + Clone + Send + Sync + 'static + serde::Serialize,
+{
+	fn proxy_details(
+		&self,
+		name: Bytes,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<ProxyDetails<AccountId>>> {
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		let details =
+			self.client.runtime_api().proxy_details(at_hash, name.to_vec()).map_err(runtime_error)?;
+		Ok(details.map(Into::into))
+	}
+
+	fn name_of(&self, address: AccountId, at: Option<Block::Hash>) -> RpcResult<Option<Bytes>> {
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		let name = self.client.runtime_api().name_of(at_hash, address).map_err(runtime_error)?;
+		Ok(name.map(|name| name.into_inner().into()))
+	}
+
+	fn latest(&self, at: Option<Block::Hash>) -> RpcResult<BlockNumber> {
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client.runtime_api().latest(at_hash).map_err(runtime_error)
+	}
+}
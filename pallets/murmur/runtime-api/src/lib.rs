@@ -0,0 +1,26 @@
+//! Runtime API definition for the murmur pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_murmur::{MurmurProxyDetails, Name};
+
+sp_api::decl_runtime_apis! {
+	/// API to look up murmur wallets and the height their OTPs are verified at
+	pub trait MurmurApi<AccountId, BlockNumber> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// Return the details of the murmur proxy called `name`, if it is registered
+		/// the name is normalized first, so it may be given in any case
+		fn proxy_details(name: Vec<u8>) -> Option<MurmurProxyDetails<AccountId>>;
+
+		/// Return the name of the murmur proxy at `address`, if it is one
+		fn name_of(address: AccountId) -> Option<Name>;
+
+		/// Return the beacon height whose OTP is verified by calls included in the next block
+		fn latest() -> BlockNumber;
+	}
+}
@@ -1,29 +1,279 @@
-// //! Benchmarking setup for pallet-etf
-// #![cfg(feature = "runtime-benchmarks")]
-// use super::*;
-
-// #[allow(unused)]
-// use crate::Pallet as Etf;
-// use frame_benchmarking::v2::*;
-// use frame_system::RawOrigin;
-
-// #[benchmarks]
-// mod benchmarks {
-// 	use super::*;
-
-// 	#[benchmark]
-// 	fn update_ibe_params() {
-// 		let g1_bytes = array_bytes::hex2bytes_unchecked("a191b705ef18a6e4e5bd4cc56de0b8f94b1f3c908f3e3fcbd4d1dc12eb85059be7e7d801edc1856c8cfbe6d63a681c1f");
-// 		let g2_bytes = array_bytes::hex2bytes_unchecked("878c5832d9519a9a22cee4d790be6bef6a0bc55e2c4c38185bf497061fb2712309f59e9eed0cdac8f8c97a61427bf35003065d0f83dca6defed8f50d715bb9430375153dff0b52bae38acf8d3aeb1612248856a8deae883f32dacaa04e3fba26");
-// 		#[extrinsic_call]
-// 		update_ibe_params(RawOrigin::Root, g1_bytes.clone(), g2_bytes.clone(), g2_bytes.clone());
-// 		assert_eq!(IBEParams::<T>::get(), (g1_bytes.clone(), g2_bytes.clone(), g2_bytes.clone()));
-// 	}
-
-// 	impl_benchmark_test_suite!(
-//         Etf, crate::mock::new_test_ext(
-// 			&"a191b705ef18a6e4e5bd4cc56de0b8f94b1f3c908f3e3fcbd4d1dc12eb85059be7e7d801edc1856c8cfbe6d63a681c1f",
-// 			&"878c5832d9519a9a22cee4d790be6bef6a0bc55e2c4c38185bf497061fb2712309f59e9eed0cdac8f8c97a61427bf35003065d0f83dca6defed8f50d715bb9430375153dff0b52bae38acf8d3aeb1612248856a8deae883f32dacaa04e3fba26",
-// 		),
-//         crate::mock::Test);
-// }
+//! Benchmarking setup for pallet-murmur
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Murmur;
+use frame_benchmarking::v1::{account, benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, Hash};
+
+fn funded<T: Config>(who: &T::AccountId) {
+	<T as pallet_proxy::Config>::Currency::make_free_balance_be(
+		who,
+		BalanceOf::<T>::max_value() / 2u32.into(),
+	);
+}
+
+/// Create a funded murmur proxy with a spending limit, whose MMR is `T::BenchmarkHelper::mmr(p)`
+fn create_wallet<T: Config>(
+	name: &Name,
+	p: u32,
+) -> Result<MurmurProxyDetails<T::AccountId>, &'static str> {
+	let spawner: T::AccountId = account("spawner", 0, 0);
+	funded::<T>(&spawner);
+	let (root, size) = T::BenchmarkHelper::mmr(p);
+	Murmur::<T>::create(
+		RawOrigin::Signed(spawner).into(),
+		root,
+		size,
+		name.clone(),
+		T::ProxyType::default(),
+		Some(SpendingLimit { amount: BalanceOf::<T>::max_value(), period: 1u32.into() }),
+	)?;
+	let details = Registry::<T>::get(name).ok_or("the wallet was not created")?;
+	funded::<T>(&details.address);
+	Ok(details)
+}
+
+/// A relayer that can pay for the calls it submits
+fn relayer<T: Config>() -> T::AccountId {
+	let relayer: T::AccountId = whitelisted_caller();
+	funded::<T>(&relayer);
+	relayer
+}
+
+// Every call authorized by an OTP is benchmarked with a valid OTP, so the weight includes its
+// verification. The hash and proof items of an OTP are at most 32 bytes and its ciphertext at
+// most `MaxCiphertextLen` bytes, which a runtime sets to the length of the ciphertexts of the
+// murmur client, so only the length `p` of its merkle proof varies.
+benchmarks! {
+	create {
+		let caller: T::AccountId = whitelisted_caller();
+		funded::<T>(&caller);
+		let name: Name = BoundedVec::truncate_from(vec![b'a'; 32]);
+		let limit = SpendingLimit { amount: BalanceOf::<T>::max_value(), period: 1u32.into() };
+	}: _(RawOrigin::Signed(caller), vec![0; 32], 1, name.clone(), T::ProxyType::default(), Some(limit))
+	verify {
+		assert!(Registry::<T>::get(name).is_some());
+	}
+
+	// for a proxy with a spending limit, apart from the weight of the dispatched call
+	proxy {
+		let p in 1 .. 8;
+		let name: Name = BoundedVec::truncate_from(vec![b'a'; 32]);
+		create_wallet::<T>(&name, p)?;
+		let call: <T as pallet_proxy::Config>::RuntimeCall =
+			frame_system::Call::<T>::remark { remark: vec![] }.into();
+		let relayer = relayer::<T>();
+		let payload = Murmur::<T>::proxy_payload(&Some(relayer.clone()), &call);
+		let otp = T::BenchmarkHelper::otp_proof(p, payload);
+	}: _(
+		RawOrigin::Signed(relayer),
+		name.clone(),
+		otp.position,
+		otp.hash,
+		otp.ciphertext,
+		otp.proof,
+		sp_std::boxed::Box::new(call),
+		true
+	)
+	verify {
+		assert!(LastExecuted::<T>::get(&name).is_some());
+	}
+
+	update_root {
+		let p in 1 .. 8;
+		let l in 1 .. 1024;
+		let name: Name = BoundedVec::truncate_from(vec![b'a'; 32]);
+		let details = create_wallet::<T>(&name, p)?;
+
+		// the MMR of the helper is perfect, so its root is its only peak
+		let peak = details.root.to_vec();
+		let store = MemStore::<Leaf>::default();
+		let mut writer = &store;
+		for pos in get_peaks(details.size) {
+			writer.append(pos, vec![Leaf(peak.clone())]).map_err(|_| "failed to build the MMR")?;
+		}
+		let mut mmr = MMR::<Leaf, MergeLeaves, &MemStore<Leaf>>::new(details.size, &store);
+		let leaves: Vec<Vec<u8>> = (0..l)
+			.map(|i| T::Hashing::hash_of(&i).as_ref().to_vec())
+			.collect();
+		for leaf in leaves.iter() {
+			mmr.push(Leaf(leaf.clone())).map_err(|_| "failed to build the MMR")?;
+		}
+		let new_root = mmr.get_root().map_err(|_| "failed to build the MMR")?.0;
+		let new_size = mmr.mmr_size();
+		let relayer = relayer::<T>();
+		let payload = Murmur::<T>::update_root_payload(&Some(relayer.clone()), &new_root, new_size);
+		let otp = T::BenchmarkHelper::otp_proof(p, payload);
+	}: _(
+		RawOrigin::Signed(relayer),
+		name.clone(),
+		otp.position,
+		otp.hash,
+		otp.ciphertext,
+		otp.proof,
+		new_root.clone(),
+		new_size,
+		vec![peak],
+		leaves
+	)
+	verify {
+		assert_eq!(Registry::<T>::get(&name).map(|d| d.root.to_vec()), Some(new_root));
+	}
+
+	// with a guardian and a pending guardian to remove
+	transfer_name {
+		let p in 1 .. 8;
+		let name: Name = BoundedVec::truncate_from(vec![b'a'; 32]);
+		let details = create_wallet::<T>(&name, p)?;
+		Murmur::<T>::do_set_guardian(
+			&name,
+			&details.address,
+			Some(account("guardian", 0, 0)),
+			T::ProxyType::default(),
+			T::MinGuardianDelay::get(),
+		)?;
+		let pending: (T::AccountId, _, _) =
+			(account("guardian", 1, 0), T::ProxyType::default(), T::MinGuardianDelay::get());
+		PendingGuardians::<T>::insert(&name, (BlockNumberFor::<T>::zero(), pending));
+		let new_root = vec![1; 32];
+		let relayer = relayer::<T>();
+		let payload = Murmur::<T>::transfer_payload(&Some(relayer.clone()), &new_root, 1);
+		let otp = T::BenchmarkHelper::otp_proof(p, payload);
+	}: _(
+		RawOrigin::Signed(relayer),
+		name.clone(),
+		otp.position,
+		otp.hash,
+		otp.ciphertext,
+		otp.proof,
+		new_root,
+		1
+	)
+	verify {
+		assert!(Guardians::<T>::get(&name).is_none());
+		assert!(PendingGuardians::<T>::get(&name).is_none());
+	}
+
+	// with a guardian to remove
+	close {
+		let p in 1 .. 8;
+		let name: Name = BoundedVec::truncate_from(vec![b'a'; 32]);
+		let details = create_wallet::<T>(&name, p)?;
+		Murmur::<T>::do_set_guardian(
+			&name,
+			&details.address,
+			Some(account("guardian", 0, 0)),
+			T::ProxyType::default(),
+			T::MinGuardianDelay::get(),
+		)?;
+		let dest: T::AccountId = account("dest", 0, 0);
+		let relayer = relayer::<T>();
+		let payload = Murmur::<T>::close_payload(&Some(relayer.clone()), &dest);
+		let otp = T::BenchmarkHelper::otp_proof(p, payload);
+	}: _(
+		RawOrigin::Signed(relayer),
+		name.clone(),
+		otp.position,
+		otp.hash,
+		otp.ciphertext,
+		otp.proof,
+		dest
+	)
+	verify {
+		assert!(Registry::<T>::get(&name).is_none());
+	}
+
+	// removing a guardian, which costs more than scheduling one
+	set_guardian {
+		let p in 1 .. 8;
+		let name: Name = BoundedVec::truncate_from(vec![b'a'; 32]);
+		let details = create_wallet::<T>(&name, p)?;
+		Murmur::<T>::do_set_guardian(
+			&name,
+			&details.address,
+			Some(account("guardian", 0, 0)),
+			T::ProxyType::default(),
+			T::MinGuardianDelay::get(),
+		)?;
+		let guardian: Option<T::AccountId> = None;
+		let delay: BlockNumberFor<T> = Zero::zero();
+		let relayer = relayer::<T>();
+		let proxy_type = T::ProxyType::default();
+		let payload =
+			Murmur::<T>::guardian_payload(&Some(relayer.clone()), &guardian, &proxy_type, delay);
+		let otp = T::BenchmarkHelper::otp_proof(p, payload);
+	}: _(
+		RawOrigin::Signed(relayer),
+		name.clone(),
+		otp.position,
+		otp.hash,
+		otp.ciphertext,
+		otp.proof,
+		guardian.clone(),
+		proxy_type,
+		delay
+	)
+	verify {
+		assert!(Guardians::<T>::get(&name).is_none());
+	}
+
+	set_name_reserved {
+		let name: Name = BoundedVec::truncate_from(vec![b'a'; 32]);
+		let origin = T::ReserveNameOrigin::try_successful_origin()
+			.map_err(|_| "no origin can reserve names")?;
+	}: _<T::RuntimeOrigin>(origin, name.clone(), true)
+	verify {
+		assert!(ReservedNames::<T>::contains_key(&name));
+	}
+
+	// with a pending limit that is due
+	set_spending_limit {
+		let p in 1 .. 8;
+		let name: Name = BoundedVec::truncate_from(vec![b'a'; 32]);
+		create_wallet::<T>(&name, p)?;
+		let pending = SpendingLimit { amount: BalanceOf::<T>::max_value(), period: 1u32.into() };
+		PendingSpendingLimits::<T>::insert(&name, (BlockNumberFor::<T>::zero(), Some(pending)));
+		let limit = Some(SpendingLimit { amount: 1u32.into(), period: 1u32.into() });
+		let relayer = relayer::<T>();
+		let payload = Murmur::<T>::limit_payload(&Some(relayer.clone()), &limit);
+		let otp = T::BenchmarkHelper::otp_proof(p, payload);
+	}: _(
+		RawOrigin::Signed(relayer),
+		name.clone(),
+		otp.position,
+		otp.hash,
+		otp.ciphertext,
+		otp.proof,
+		limit.clone()
+	)
+	verify {
+		assert_eq!(SpendingLimits::<T>::get(&name), limit);
+		assert!(PendingSpendingLimits::<T>::get(&name).is_none());
+	}
+
+	// replacing a guardian
+	apply_guardian {
+		let name: Name = BoundedVec::truncate_from(vec![b'a'; 32]);
+		let details = create_wallet::<T>(&name, 1)?;
+		Murmur::<T>::do_set_guardian(
+			&name,
+			&details.address,
+			Some(account("guardian", 0, 0)),
+			T::ProxyType::default(),
+			T::MinGuardianDelay::get(),
+		)?;
+		let guardian: T::AccountId = account("guardian", 1, 0);
+		let pending = (guardian.clone(), T::ProxyType::default(), T::MinGuardianDelay::get());
+		PendingGuardians::<T>::insert(&name, (BlockNumberFor::<T>::zero(), pending));
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), name.clone())
+	verify {
+		assert_eq!(Guardians::<T>::get(&name).map(|(g, _, _)| g), Some(guardian));
+		assert!(PendingGuardians::<T>::get(&name).is_none());
+	}
+
+	impl_benchmark_test_suite!(Murmur, crate::mock::new_test_ext(vec![0]), crate::mock::Test);
+}
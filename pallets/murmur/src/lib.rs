@@ -14,22 +14,38 @@ mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
-// pub mod weights; TODO
-// pub use weights::WeightInfo;
+pub mod migrations;
+pub mod weights;
+pub use weights::WeightInfo;
 
-use log::info;
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
+use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
 use sp_std::{vec, vec::Vec, prelude::ToOwned};
 use frame_support::{
 	pallet_prelude::*,
-	traits::{ConstU32, IsSubType},
-	dispatch::GetDispatchInfo,
+	traits::{
+		ConstU32, Currency, ExistenceRequirement, InstanceFilter, IsSubType, OnUnbalanced,
+		ReservableCurrency, WithdrawReasons,
+	},
+	dispatch::{
+		extract_actual_weight, DispatchResultWithPostInfo, GetDispatchInfo, Pays,
+		PostDispatchInfo, WithPostDispatchInfo,
+	},
+	weights::WeightToFee,
+};
+use sp_runtime::{
+	DispatchResult,
+	traits::{BadOrigin, Dispatchable, Saturating, StaticLookup, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionLongevity, TransactionPriority, TransactionSource,
+		TransactionValidity, ValidTransaction,
+	},
 };
-use sp_runtime::{DispatchResult, traits::Dispatchable};
 use ckb_merkle_mountain_range::{
 	MerkleProof,
-    MMR, Merge, Result as MMRResult,
+    MMR, MMRStore, Merge, Result as MMRResult,
+    helper::{get_peaks, pos_height_in_tree},
     util::{MemMMR, MemStore},
 };
 use murmur_core::{
@@ -40,23 +56,116 @@ use murmur_core::{
 	}
 };
 
-use pallet_randomness_beacon::{Ciphertext, TimelockEncryptionProvider};
+use pallet_randomness_beacon::{Ciphertext, TimelockEncryptionProvider, TimelockError};
 
 /// a bounded name
 pub type Name = BoundedVec<u8, ConstU32<32>>;
 
+/// the root of the MMR of a murmur proxy, which is a 32 byte hash
+pub type Root = BoundedVec<u8, ConstU32<32>>;
+
+/// The length of the hashes in an OTP, which are its commitment and the nodes of its proof
+const HASH_LENGTH: usize = 32;
+
+/// An OTP is only valid until the beacon moves on, so unsigned calls are short-lived
+const UNSIGNED_LONGEVITY: TransactionLongevity = 3;
+
+/// Domain separators for the payloads that OTPs commit to, so that an OTP authorizing
+/// one kind of call cannot be used for another
+const PROXY_TAG: &[u8] = b"murmur/proxy";
+const UPDATE_ROOT_TAG: &[u8] = b"murmur/update_root";
+const TRANSFER_TAG: &[u8] = b"murmur/transfer_name";
+const CLOSE_TAG: &[u8] = b"murmur/close";
+const GUARDIAN_TAG: &[u8] = b"murmur/set_guardian";
+const LIMIT_TAG: &[u8] = b"murmur/set_spending_limit";
+
+pub type BalanceOf<T> = <<T as pallet_proxy::Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::Balance;
+
+pub type NegativeImbalanceOf<T> = <<T as pallet_proxy::Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+pub type SpendingLimitOf<T> = SpendingLimit<BalanceOf<T>, BlockNumberFor<T>>;
+
+/// How much of the balance of a murmur proxy a call spends, which counts against its spending limit
+/// calls that dispatch other calls, such as batches, should count the calls they dispatch
+pub trait CallSpend<Call, Balance> {
+	fn spend(call: &Call) -> Balance;
+}
+
+/// Counts nothing, so spending limits are never reached by proxied calls
+/// only the sweep of a closed proxy is still limited
+impl<Call, Balance: Zero> CallSpend<Call, Balance> for () {
+	fn spend(_call: &Call) -> Balance {
+		Zero::zero()
+	}
+}
+
+/// An OTP of a murmur proxy and the proof that it is in the MMR of the proxy
+#[cfg(feature = "runtime-benchmarks")]
+pub struct OtpProof {
+	/// The position of the ciphertext in the MMR
+	pub position: u64,
+	/// The commitment to the OTP and the payload
+	pub hash: Vec<u8>,
+	/// The ciphertext of the OTP
+	pub ciphertext: Vec<u8>,
+	/// The merkle proof of the ciphertext
+	pub proof: Vec<Vec<u8>>,
+}
+
+/// Provides OTPs that can be verified, to benchmark the calls of murmur proxies
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper {
+	/// The root and size of an MMR of `2^p` OTPs for the heights from the current beacon height
+	/// on, so that the proof of the first OTP has `p` items
+	fn mmr(p: u32) -> (Vec<u8>, u64);
+
+	/// The OTP for the current beacon height in the MMR of `mmr(p)`, whose hash commits to
+	/// `payload`
+	/// the beacon must be able to decrypt its ciphertext when the benchmark runs
+	fn otp_proof(p: u32, payload: Vec<u8>) -> OtpProof;
+}
+
+/// Where and by whom the pure proxy of a murmur wallet was spawned, which is needed to kill it
+/// along with the deposit the spawner holds for the name
+#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct PureProxySpawn<AccountId, BlockNumber, Balance, ProxyType> {
+	/// The account that created the murmur proxy
+	pub spawner: AccountId,
+	/// The block in which the pure proxy was created
+	pub height: BlockNumber,
+	/// The index of the extrinsic that created the pure proxy
+	pub ext_index: u32,
+	/// The deposit reserved from the spawner for the name
+	pub deposit: Balance,
+	/// The type of the pure proxy, which filters the calls it can dispatch
+	pub proxy_type: ProxyType,
+}
+
+/// The most a murmur proxy can spend in each period of `period` blocks
+#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct SpendingLimit<Balance, BlockNumber> {
+	/// The most the proxy can spend in a period
+	pub amount: Balance,
+	/// The length of a period in blocks
+	pub period: BlockNumber,
+}
+
 /// A struct to represent specific details of a murmur proxy account
 #[derive(
 	Debug, 
 	PartialEq, Eq, Hash, 
-	Clone, Encode, Decode, TypeInfo, 
+	Clone, Encode, Decode, TypeInfo, MaxEncodedLen,
 	serde::Serialize, serde::Deserialize
 )]
 pub struct MurmurProxyDetails<AccountId> {
 	/// The proxy account address
 	pub address: AccountId,
 	/// The MMR root
-	pub root: Vec<u8>,
+	pub root: Root,
 	/// The MMR size
 	pub size: u64,
 }
@@ -70,8 +179,11 @@ pub mod pallet {
 	};
 	use frame_system::pallet_prelude::*;
 
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
-	#[pallet::without_storage_info]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
@@ -86,10 +198,49 @@ pub mod pallet {
 			+ From<frame_system::Call<Self>>
 			+ IsSubType<Call<Self>>
 			+ IsType<<Self as frame_system::Config>::RuntimeCall>;
-		// / Type representing the weight of this pallet
-		// type WeightInfo: WeightInfo;
+		/// Type representing the weight of this pallet
+		type WeightInfo: WeightInfo;
 		/// something that can decrypt messages locked for the current slot
 		type TlockProvider: TimelockEncryptionProvider<BlockNumberFor<Self>>;
+		/// Converts the weight of a verified call to the fee the proxy pays for it
+		type WeightToFee: WeightToFee<Balance = BalanceOf<Self>>;
+		/// Handler for the fees paid by murmur proxies for unsigned calls
+		type OnFeeCharged: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// The most a relayer is repaid by a murmur proxy for submitting one of its calls
+		#[pallet::constant]
+		type MaxRelayerFee: Get<BalanceOf<Self>>;
+		/// The priority of unsigned calls authorized by their OTP
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+		/// The deposit reserved for registering a name, on top of the per byte deposit
+		#[pallet::constant]
+		type NameDepositBase: Get<BalanceOf<Self>>;
+		/// The deposit reserved per byte of a registered name
+		#[pallet::constant]
+		type NameDepositPerByte: Get<BalanceOf<Self>>;
+		/// The origin that can reserve names so that they cannot be registered
+		type ReserveNameOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// How much a proxied call spends, which is counted against the spending limit of the proxy
+		///
+		/// `()` counts nothing, which leaves spending limits unenforced for proxied calls, so
+		/// runtimes that let wallets set spending limits must count at least the calls that
+		/// transfer funds, including those dispatched by batches and other proxies
+		type CallSpend: CallSpend<<Self as pallet_proxy::Config>::RuntimeCall, BalanceOf<Self>>;
+		/// How long a raised or removed spending limit or a new guardian waits before it applies,
+		/// so that the owner can override it if it was set with a leaked OTP
+		#[pallet::constant]
+		type SpendingLimitDelay: Get<BlockNumberFor<Self>>;
+		/// The shortest delay with which the calls of a guardian must be announced, so that the
+		/// owner has time to reject them
+		#[pallet::constant]
+		type MinGuardianDelay: Get<BlockNumberFor<Self>>;
+		/// The longest ciphertext of an OTP, which should be the length of the ciphertexts that
+		/// the murmur client produces, since the weights are benchmarked with one of them
+		#[pallet::constant]
+		type MaxCiphertextLen: Get<u32>;
+		/// Helper to benchmark the verification of OTPs
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper;
 	}
 
 	/// a registry to track registered 'usernames' for OTP wallets
@@ -98,54 +249,214 @@ pub mod pallet {
 	pub(super) type Registry<T: Config> =
 		StorageMap<_, Blake2_256, Name, MurmurProxyDetails<T::AccountId>, OptionQuery>;
 
+	/// the name of each murmur proxy, by its address
+	#[pallet::storage]
+	pub(super) type Names<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, Name, OptionQuery>;
+
+	/// the beacon height at which each murmur proxy last executed a call
+	/// each OTP is locked to a single height, so a proxy can only execute once per height
+	#[pallet::storage]
+	pub(super) type LastExecuted<T: Config> =
+		StorageMap<_, Blake2_256, Name, BlockNumberFor<T>, OptionQuery>;
+
+	/// where the pure proxy of each murmur proxy was spawned
+	/// it was not recorded for proxies created before storage version 1
+	#[pallet::storage]
+	pub(super) type Spawns<T: Config> = StorageMap<
+		_,
+		Blake2_256,
+		Name,
+		PureProxySpawn<T::AccountId, BlockNumberFor<T>, BalanceOf<T>, T::ProxyType>,
+		OptionQuery,
+	>;
+
+	/// names that cannot be registered, such as well-known names
+	#[pallet::storage]
+	pub(super) type ReservedNames<T: Config> = StorageMap<_, Blake2_256, Name, (), OptionQuery>;
+
+	/// the recovery guardian of each murmur proxy, its proxy type and the delay of its announcements
+	#[pallet::storage]
+	pub(super) type Guardians<T: Config> = StorageMap<
+		_,
+		Blake2_256,
+		Name,
+		(T::AccountId, T::ProxyType, BlockNumberFor<T>),
+		OptionQuery,
+	>;
+
+	/// the spending limit of each murmur proxy that has one
+	#[pallet::storage]
+	pub(super) type SpendingLimits<T: Config> =
+		StorageMap<_, Blake2_256, Name, SpendingLimitOf<T>, OptionQuery>;
+
+	/// the spending limit that each murmur proxy raised or removed, and the block from which
+	/// it applies
+	#[pallet::storage]
+	pub(super) type PendingSpendingLimits<T: Config> = StorageMap<
+		_,
+		Blake2_256,
+		Name,
+		(BlockNumberFor<T>, Option<SpendingLimitOf<T>>),
+		OptionQuery,
+	>;
+
+	/// the guardian that each murmur proxy set, and the block from which it can be applied
+	#[pallet::storage]
+	pub(super) type PendingGuardians<T: Config> = StorageMap<
+		_,
+		Blake2_256,
+		Name,
+		(BlockNumberFor<T>, (T::AccountId, T::ProxyType, BlockNumberFor<T>)),
+		OptionQuery,
+	>;
+
+	/// the first block of the current period of each murmur proxy with a spending limit
+	/// and how much it has spent since
+	#[pallet::storage]
+	pub(super) type Spent<T: Config> =
+		StorageMap<_, Blake2_256, Name, (BlockNumberFor<T>, BalanceOf<T>), OptionQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		OtpProxyCreated,
-		OtpProxyExecuted
+		/// A murmur proxy dispatched a call authorized by the OTP at `position`
+		OtpProxyExecuted { name: Name, position: u64, result: DispatchResult },
+		/// A murmur proxy moved to a new MMR root
+		OtpProxyRootUpdated { name: Name, address: T::AccountId },
+		/// An OTP of a murmur proxy was verified and marked as used
+		OtpVerified { name: Name, position: u64, when: BlockNumberFor<T> },
+		/// A murmur proxy repaid the relayer that submitted its call
+		RelayerRepaid { name: Name, relayer: T::AccountId, amount: BalanceOf<T> },
+		/// A murmur proxy was handed over to the holder of a new MMR
+		OtpProxyTransferred { name: Name, address: T::AccountId },
+		/// A murmur proxy was killed and its funds swept to `dest`
+		OtpProxyClosed { name: Name, address: T::AccountId, dest: T::AccountId },
+		/// A name was reserved or released
+		NameReservationSet { name: Name, reserved: bool },
+		/// The recovery guardian of a murmur proxy was set or removed
+		GuardianSet {
+			name: Name,
+			address: T::AccountId,
+			guardian: Option<T::AccountId>,
+			proxy_type: T::ProxyType,
+			delay: BlockNumberFor<T>,
+		},
+		/// The recovery guardian of a murmur proxy can be set from block `at`
+		GuardianScheduled {
+			name: Name,
+			guardian: T::AccountId,
+			proxy_type: T::ProxyType,
+			delay: BlockNumberFor<T>,
+			at: BlockNumberFor<T>,
+		},
+		/// The spending limit of a murmur proxy was set or removed
+		SpendingLimitSet { name: Name, limit: Option<SpendingLimitOf<T>> },
+		/// The spending limit of a murmur proxy will be raised or removed at block `at`
+		SpendingLimitScheduled {
+			name: Name,
+			limit: Option<SpendingLimitOf<T>>,
+			at: BlockNumberFor<T>,
+		},
 	}
 
 	// Errors inform users that something went wrong.
 	#[pallet::error]
 	pub enum Error<T> {
+		/// The ciphertext is malformed or could not be decrypted
 		BadCiphertext,
+		/// The beacon has not produced the pulse needed to decrypt the ciphertext
+		MissingPulse,
+		/// The pulse needed to decrypt the ciphertext does not match the active round key
+		WrongKeyEpoch,
 		DuplicateName,
+		/// The OTP does not match the hash
 		InvalidOTP,
+		/// The merkle proof does not prove the ciphertext is in the MMR of the proxy
 		InvalidMerkleProof,
+		/// The merkle proof does not match the size of the MMR of the proxy
+		MalformedProof,
+		/// The position is not the position of a leaf in the MMR of the proxy
+		InvalidPosition,
 		InvalidProxy,
 		ProxyDNE,
+		/// The OTP for the current height has already been used
+		ReplayedOTP,
+		/// The new MMR does not extend the current MMR of the proxy
+		InvalidExtension,
+		/// The name is empty or contains characters other than ASCII letters, digits, `-` and `_`
+		InvalidName,
+		/// The name is reserved
+		ReservedName,
+		/// The MMR root is longer than 32 bytes
+		InvalidRoot,
+		/// The period of the spending limit is zero
+		InvalidSpendingLimit,
+		/// The call would spend more than the proxy can in the current period
+		SpendingLimitExceeded,
+		/// The proxy type of the guardian allows calls that the murmur proxy cannot dispatch
+		InvalidGuardianType,
+		/// The calls of the guardian would be announced less than `MinGuardianDelay` in advance
+		GuardianDelayTooShort,
+		/// The murmur proxy has no pending guardian, or it cannot be set yet
+		NoDueGuardian,
+		/// The hash, ciphertext or a proof item of the OTP is longer than allowed
+		OversizedOtp,
 	}
  
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			Self::validate_unsigned(call)
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 
 		/// Create a time-based proxy account
+		/// the name is normalized to lowercase and a deposit that grows with its length is
+		/// reserved from the caller until the proxy is closed
 		///
 		/// * `root`: The MMR root
-		/// * `size`: The size (number of leaves) of the MMR
+		/// * `size`: The size (number of nodes) of the MMR
 		/// * `name`: The name to assign to the murmur proxy
+		/// * `proxy_type`: The type of the pure proxy, which filters the calls it can dispatch
+		/// * `spending_limit`: The most the proxy can spend per period, if it is limited
 		///
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::create())]
 		#[pallet::call_index(0)]
 		pub fn create(
 			origin: OriginFor<T>,
 			root: Vec<u8>,
 			size: u64,
 			name: BoundedVec<u8, ConstU32<32>>,
+			proxy_type: T::ProxyType,
+			spending_limit: Option<SpendingLimitOf<T>>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			let name = Self::normalize(&name).ok_or(Error::<T>::InvalidName)?;
+			let root = Root::try_from(root).map_err(|_| Error::<T>::InvalidRoot)?;
+			Self::ensure_valid_limit(&spending_limit)?;
 
 			// check duplicate name
 			ensure!(
 				Registry::<T>::get(name.clone()).is_none(), 
 				Error::<T>::DuplicateName
 			);
+			ensure!(!ReservedNames::<T>::contains_key(&name), Error::<T>::ReservedName);
+
+			let deposit = Self::name_deposit(&name);
+			<T as pallet_proxy::Config>::Currency::reserve(&who, deposit)?;
 
 			// create a pure proxy with no delegate
 			let signed_origin: T::RuntimeOrigin = frame_system::RawOrigin::Signed(who.clone()).into();
 			pallet_proxy::Pallet::<T>::create_pure(
 				signed_origin,
-				T::ProxyType::default(),
+				proxy_type.clone(),
 				BlockNumberFor::<T>::zero(),
 				0u16,
 				true,
@@ -153,10 +464,21 @@ pub mod pallet {
 
 			let address = pallet_proxy::Pallet::<T>::pure_account(
 				&who, 
-				&T::ProxyType::default(), 
+				&proxy_type, 
 				0, None
 			);
+			Spawns::<T>::insert(&name, PureProxySpawn {
+				spawner: who,
+				height: frame_system::Pallet::<T>::block_number(),
+				ext_index: frame_system::Pallet::<T>::extrinsic_index().unwrap_or_default(),
+				deposit,
+				proxy_type,
+			});
+			if let Some(limit) = spending_limit {
+				SpendingLimits::<T>::insert(&name, limit);
+			}
 			
+			Names::<T>::insert(&address, &name);
 			Registry::<T>::insert(name, &MurmurProxyDetails { address, root, size });
 			Self::deposit_event(Event::OtpProxyCreated);
 
@@ -167,71 +489,968 @@ pub mod pallet {
 		/// this function first checks the validity of the merkle proof (using the ciphertext)
 		/// if valid, it decrypts the ciphertext and uses it to verify the hash
 		/// if valid, it proxies the call
+		/// the hash commits to the encoded `("murmur/proxy", relayer, call)`, where `relayer` is
+		/// the signer of this call, or `None` if it is unsigned, so that no one else can submit it
+		///
+		/// the caller can be anyone, such as a relayer for the owner of the proxy
+		/// once the OTP is verified and before the call is dispatched, the proxy repays a signed
+		/// relayer the fee for the weight of this call, up to `MaxRelayerFee`, if it can
+		/// the call can also be unsigned, in which case the OTP authorizes the proxy to pay the fee,
+		/// and this call fails if it cannot
+		/// the call is filtered by the proxy type of the proxy, and what it spends must be
+		/// within the spending limit of the proxy, against which it only counts if it succeeds
+		///
+		/// the result of the call is deposited in an `OtpProxyExecuted` event
+		/// if `fail_on_error` is set, this call fails when the proxied call does, which reverts
+		/// the use of the OTP and the repayment of the relayer, so it cannot be unsigned
 		///
 		/// * `name`: The uid of the murmur proxy
 		/// * `position`: The position in the MMR of the encrypted OTP code
-		/// * `target_leaf`: The target leaf data (ciphertext)
 		/// * `hash`: A hash to commit to the OTP code and call data
-		/// * `proof`: A merkle proof the the target leaf is in the expected MMR
+		/// * `ciphertext`: The target leaf data (ciphertext)
+		/// * `proof`: A merkle proof the the target leaf is in the registered MMR
 		/// * `call`: The call to be proxied
+		/// * `fail_on_error`: Whether this call fails when the proxied call does
 		///
-		#[pallet::weight(0)]
+		#[pallet::weight({
+			let di = call.get_dispatch_info();
+			(T::WeightInfo::proxy(proof.len() as u32).saturating_add(di.weight), di.class)
+		})]
 		#[pallet::call_index(1)]
 		pub fn proxy(
-			_origin: OriginFor<T>,
+			origin: OriginFor<T>,
 			name: BoundedVec<u8, ConstU32<32>>,
 			position: u64,
 			hash: Vec<u8>,
 			ciphertext: Vec<u8>,
 			proof: Vec<Vec<u8>>,
-			size: u64,
 			call: sp_std::boxed::Box<<T as pallet_proxy::Config>::RuntimeCall>,
-		) -> DispatchResult {
-			// let who = ensure_signed(origin)?;
-			let when = T::TlockProvider::latest();
-
+			fail_on_error: bool,
+		) -> DispatchResultWithPostInfo {
+			let maybe_relayer = Self::ensure_relayer_or_none(origin)?;
+			ensure!(!fail_on_error || maybe_relayer.is_some(), BadOrigin);
+			let name = Self::normalize(&name).ok_or(Error::<T>::InvalidName)?;
+			let proxy_weight = T::WeightInfo::proxy(proof.len() as u32);
 			let proxy_details = Registry::<T>::get(name.clone())
 				.ok_or(Error::<T>::InvalidProxy)?;
 
-			let result = T::TlockProvider::decrypt_at(&ciphertext, when)
-				.map_err(|_| Error::<T>::BadCiphertext)?;
-			let mut otp = result.message;
-
-			let leaves: Vec<Leaf> = proof.clone().into_iter()
-				.map(|p| Leaf(p)).collect::<Vec<_>>();
-			let merkle_proof = MerkleProof::<Leaf, MergeLeaves>::new(size, leaves.clone());
-			let root = Leaf(proxy_details.root);
-
-			let test = merkle_proof.verify(root.clone(), vec![(position.clone(), Leaf(ciphertext.clone()))]);
-			log::info!("****************************************************************************************** Precheck merkle proof validity? {:?}, had mmr size {:?}", test, size);
-
-			let validity = murmur::verify(
-				root, 
-				merkle_proof, 
-				hash, 
-				ciphertext, 
-				otp, 
-				call.encode().to_vec(), 
-				position
-			);
-
-			frame_support::ensure!(validity, Error::<T>::InvalidMerkleProof);
+			Self::verify_otp(
+				&name,
+				&proxy_details,
+				position,
+				hash,
+				ciphertext,
+				proof,
+				Self::proxy_payload(&maybe_relayer, &call),
+			).map_err(|e| e.with_weight(proxy_weight))?;
 
 			let def = pallet_proxy::Pallet::<T>::find_proxy(
 				&proxy_details.address, 
 				None, 
-				Some(T::ProxyType::default())
-			).map_err(|_| Error::<T>::InvalidProxy)?;
+				Some(Self::proxy_type_of(&name))
+			).map_err(|_| Error::<T>::InvalidProxy.with_weight(proxy_weight))?;
+
+			Self::apply_pending_limit(&name);
+			let maybe_spent = Self::check_spend(&name, T::CallSpend::spend(&call))
+				.map_err(|e| e.with_weight(proxy_weight))?;
+
+			// the fee is settled before the call can spend the funds of the proxy
+			let info = call.get_dispatch_info();
+			let pays_fee = Self::settle_fee(
+				&name,
+				&proxy_details.address,
+				maybe_relayer,
+				proxy_weight.saturating_add(info.weight),
+			).map_err(|e| e.with_weight(proxy_weight))?;
+
+			let result = pallet_proxy::Pallet::<T>::do_proxy(def, proxy_details.address.clone(), *call);
+			let actual_weight = proxy_weight.saturating_add(extract_actual_weight(&result, &info));
+
+			// a failed call spent nothing, since its changes were reverted
+			if let (Ok(_), Some(spent)) = (&result, maybe_spent) {
+				Spent::<T>::insert(&name, spent);
+			}
+
+			if fail_on_error {
+				if let Err(e) = &result {
+					return Err(e.error.with_weight(actual_weight));
+				}
+			}
+
+			Self::deposit_event(Event::OtpProxyExecuted {
+				name: name.clone(),
+				position,
+				result: result.map(|_| ()).map_err(|e| e.error),
+			});
+			Ok(PostDispatchInfo { actual_weight: Some(actual_weight), pays_fee })
+		}
+
+		/// Extend the MMR of a murmur proxy with new OTP leaves
+		/// the update is authorized by an OTP for the current height, just like a proxied call,
+		/// where the hash commits to the encoded
+		/// `("murmur/update_root", relayer, new_root, new_size)`
+		/// since MMRs are append-only, the new MMR must be the current one with `leaves` pushed
+		/// onto it, which is verified by rebuilding it from the peaks of the current MMR
+		/// fees are settled just like for `proxy`
+		///
+		/// * `name`: The uid of the murmur proxy
+		/// * `position`: The position in the current MMR of the encrypted OTP code
+		/// * `hash`: A hash to commit to the OTP code and the new root and size
+		/// * `ciphertext`: The target leaf data (ciphertext)
+		/// * `proof`: A merkle proof the the target leaf is in the current MMR
+		/// * `new_root`: The root of the extended MMR
+		/// * `new_size`: The size (number of nodes) of the extended MMR
+		/// * `peaks`: The peaks of the current MMR, from left to right
+		/// * `leaves`: The leaves appended to the current MMR, in order
+		///
+		#[pallet::weight(T::WeightInfo::update_root(proof.len() as u32, leaves.len() as u32))]
+		#[pallet::call_index(2)]
+		pub fn update_root(
+			origin: OriginFor<T>,
+			name: BoundedVec<u8, ConstU32<32>>,
+			position: u64,
+			hash: Vec<u8>,
+			ciphertext: Vec<u8>,
+			proof: Vec<Vec<u8>>,
+			new_root: Vec<u8>,
+			new_size: u64,
+			peaks: Vec<Vec<u8>>,
+			leaves: Vec<Vec<u8>>,
+		) -> DispatchResultWithPostInfo {
+			let maybe_relayer = Self::ensure_relayer_or_none(origin)?;
+			let name = Self::normalize(&name).ok_or(Error::<T>::InvalidName)?;
+			let weight = T::WeightInfo::update_root(proof.len() as u32, leaves.len() as u32);
+			let mut proxy_details = Registry::<T>::get(name.clone())
+				.ok_or(Error::<T>::InvalidProxy)?;
+
+			Self::verify_otp(
+				&name,
+				&proxy_details,
+				position,
+				hash,
+				ciphertext,
+				proof,
+				Self::update_root_payload(&maybe_relayer, &new_root, new_size),
+			)?;
+
+			ensure!(
+				Self::is_extension(&proxy_details, peaks, leaves, &new_root, new_size),
+				Error::<T>::InvalidExtension
+			);
+
+			proxy_details.root = Root::try_from(new_root).map_err(|_| Error::<T>::InvalidRoot)?;
+			proxy_details.size = new_size;
+			Registry::<T>::insert(&name, &proxy_details);
+			Self::deposit_event(Event::OtpProxyRootUpdated {
+				name: name.clone(),
+				address: proxy_details.address.clone(),
+			});
+
+			Ok(Self::settle_fee(&name, &proxy_details.address, maybe_relayer, weight)?.into())
+		}
+
+		/// Hand a murmur proxy over to whoever holds the OTPs of a new MMR
+		/// unlike `update_root`, the new MMR is unrelated to the current one,
+		/// and any recovery guardian is removed
+		/// the balance of the proxy is handed over with it, so it counts against the spending
+		/// limit of the proxy just like the sweep of `close`
+		/// the hash commits to the encoded `("murmur/transfer_name", relayer, new_root, new_size)`
+		/// fees are settled just like for `proxy`
+		///
+		/// * `name`: The uid of the murmur proxy
+		/// * `position`: The position in the current MMR of the encrypted OTP code
+		/// * `hash`: A hash to commit to the OTP code and the new root and size
+		/// * `ciphertext`: The target leaf data (ciphertext)
+		/// * `proof`: A merkle proof the the target leaf is in the current MMR
+		/// * `new_root`: The root of the MMR of the new owner
+		/// * `new_size`: The size (number of nodes) of the MMR of the new owner
+		///
+		#[pallet::weight(T::WeightInfo::transfer_name(proof.len() as u32))]
+		#[pallet::call_index(3)]
+		pub fn transfer_name(
+			origin: OriginFor<T>,
+			name: BoundedVec<u8, ConstU32<32>>,
+			position: u64,
+			hash: Vec<u8>,
+			ciphertext: Vec<u8>,
+			proof: Vec<Vec<u8>>,
+			new_root: Vec<u8>,
+			new_size: u64,
+		) -> DispatchResultWithPostInfo {
+			let maybe_relayer = Self::ensure_relayer_or_none(origin)?;
+			let name = Self::normalize(&name).ok_or(Error::<T>::InvalidName)?;
+			let weight = T::WeightInfo::transfer_name(proof.len() as u32);
+			let mut proxy_details = Registry::<T>::get(name.clone())
+				.ok_or(Error::<T>::InvalidProxy)?;
+
+			Self::verify_otp(
+				&name,
+				&proxy_details,
+				position,
+				hash,
+				ciphertext,
+				proof,
+				Self::transfer_payload(&maybe_relayer, &new_root, new_size),
+			)?;
+
+			let balance =
+				<T as pallet_proxy::Config>::Currency::free_balance(&proxy_details.address);
+			Self::check_spend(&name, balance)?;
+
+			// the guardian of the previous owner must not be able to recover the proxy
+			PendingGuardians::<T>::remove(&name);
+			Self::remove_guardian(&name, &proxy_details.address)?;
+
+			proxy_details.root = Root::try_from(new_root).map_err(|_| Error::<T>::InvalidRoot)?;
+			proxy_details.size = new_size;
+			Registry::<T>::insert(&name, &proxy_details);
+			Self::deposit_event(Event::OtpProxyTransferred {
+				name: name.clone(),
+				address: proxy_details.address.clone(),
+			});
+
+			Ok(Self::settle_fee(&name, &proxy_details.address, maybe_relayer, weight)?.into())
+		}
+
+		/// Close a murmur proxy, releasing its name
+		/// the pure proxy is killed and all of its free balance is swept to `dest`,
+		/// after the fees are settled just like for `proxy`
+		/// the sweep counts against the spending limit of the proxy, so a proxy with a limit
+		/// can only be closed once its balance is within what it can still spend
+		/// the hash commits to the encoded `("murmur/close", relayer, dest)`
+		///
+		/// * `name`: The uid of the murmur proxy
+		/// * `position`: The position in the MMR of the encrypted OTP code
+		/// * `hash`: A hash to commit to the OTP code and the destination
+		/// * `ciphertext`: The target leaf data (ciphertext)
+		/// * `proof`: A merkle proof the the target leaf is in the registered MMR
+		/// * `dest`: The account that receives the funds of the proxy
+		///
+		#[pallet::weight(T::WeightInfo::close(proof.len() as u32))]
+		#[pallet::call_index(4)]
+		pub fn close(
+			origin: OriginFor<T>,
+			name: BoundedVec<u8, ConstU32<32>>,
+			position: u64,
+			hash: Vec<u8>,
+			ciphertext: Vec<u8>,
+			proof: Vec<Vec<u8>>,
+			dest: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let maybe_relayer = Self::ensure_relayer_or_none(origin)?;
+			let name = Self::normalize(&name).ok_or(Error::<T>::InvalidName)?;
+			let weight = T::WeightInfo::close(proof.len() as u32);
+			let proxy_details = Registry::<T>::get(name.clone())
+				.ok_or(Error::<T>::InvalidProxy)?;
 
-			pallet_proxy::Pallet::<T>::do_proxy(def, proxy_details.address, *call);
+			Self::verify_otp(
+				&name,
+				&proxy_details,
+				position,
+				hash,
+				ciphertext,
+				proof,
+				Self::close_payload(&maybe_relayer, &dest),
+			)?;
+
+			let pays_fee = Self::settle_fee(&name, &proxy_details.address, maybe_relayer, weight)?;
+			Self::do_close(&name, &proxy_details.address, dest)?;
+
+			Ok(pays_fee.into())
+		}
+
+		/// Set or remove the recovery guardian of a murmur proxy
+		/// the guardian is a delegate of the pure proxy whose calls must be announced `delay`
+		/// blocks in advance with `pallet_proxy::announce`, so that the owner can reject them
+		/// by proxying `pallet_proxy::reject_announcement`
+		/// the guardian can only dispatch the calls allowed by `proxy_type`, which cannot allow
+		/// more than the proxy type of the murmur proxy
+		/// removing the guardian applies at once and cancels a pending guardian, while a new
+		/// guardian can only be set with `apply_guardian` after `SpendingLimitDelay`, so that the
+		/// owner can remove a guardian set with a leaked OTP before it can announce calls
+		/// the hash commits to the encoded
+		/// `("murmur/set_guardian", relayer, guardian, proxy_type, delay)`
+		/// fees are settled just like for `proxy`
+		///
+		/// * `name`: The uid of the murmur proxy
+		/// * `position`: The position in the MMR of the encrypted OTP code
+		/// * `hash`: A hash to commit to the OTP code, the guardian and the delay
+		/// * `ciphertext`: The target leaf data (ciphertext)
+		/// * `proof`: A merkle proof the the target leaf is in the registered MMR
+		/// * `guardian`: The new guardian, or `None` to remove the current one
+		/// * `proxy_type`: The type of the guardian, which filters the calls it can dispatch
+		/// * `delay`: The number of blocks the calls of the guardian must be announced in advance
+		///
+		#[pallet::weight(T::WeightInfo::set_guardian(proof.len() as u32))]
+		#[pallet::call_index(5)]
+		pub fn set_guardian(
+			origin: OriginFor<T>,
+			name: BoundedVec<u8, ConstU32<32>>,
+			position: u64,
+			hash: Vec<u8>,
+			ciphertext: Vec<u8>,
+			proof: Vec<Vec<u8>>,
+			guardian: Option<T::AccountId>,
+			proxy_type: T::ProxyType,
+			delay: BlockNumberFor<T>,
+		) -> DispatchResultWithPostInfo {
+			let maybe_relayer = Self::ensure_relayer_or_none(origin)?;
+			let name = Self::normalize(&name).ok_or(Error::<T>::InvalidName)?;
+			let weight = T::WeightInfo::set_guardian(proof.len() as u32);
+			let proxy_details = Registry::<T>::get(name.clone())
+				.ok_or(Error::<T>::InvalidProxy)?;
+			if guardian.is_some() {
+				Self::ensure_valid_guardian(&name, &proxy_type, delay)?;
+			}
+
+			Self::verify_otp(
+				&name,
+				&proxy_details,
+				position,
+				hash,
+				ciphertext,
+				proof,
+				Self::guardian_payload(&maybe_relayer, &guardian, &proxy_type, delay),
+			)?;
+
+			match guardian {
+				Some(guardian) => {
+					let at = frame_system::Pallet::<T>::block_number()
+						.saturating_add(T::SpendingLimitDelay::get());
+					PendingGuardians::<T>::insert(
+						&name,
+						(at, (guardian.clone(), proxy_type.clone(), delay)),
+					);
+					Self::deposit_event(Event::GuardianScheduled {
+						name: name.clone(),
+						guardian,
+						proxy_type,
+						delay,
+						at,
+					});
+				},
+				None => {
+					PendingGuardians::<T>::remove(&name);
+					Self::do_set_guardian(&name, &proxy_details.address, None, proxy_type, delay)?;
+				},
+			}
+
+			Ok(Self::settle_fee(&name, &proxy_details.address, maybe_relayer, weight)?.into())
+		}
+
+		/// Reserve or release a name
+		/// a reserved name cannot be registered, but a murmur proxy that already has it is kept
+		///
+		/// * `name`: The name to reserve or release
+		/// * `reserved`: Whether the name is reserved
+		///
+		#[pallet::weight(T::WeightInfo::set_name_reserved())]
+		#[pallet::call_index(6)]
+		pub fn set_name_reserved(
+			origin: OriginFor<T>,
+			name: BoundedVec<u8, ConstU32<32>>,
+			reserved: bool,
+		) -> DispatchResult {
+			T::ReserveNameOrigin::ensure_origin(origin)?;
+			let name = Self::normalize(&name).ok_or(Error::<T>::InvalidName)?;
+
+			if reserved {
+				ReservedNames::<T>::insert(&name, ());
+			} else {
+				ReservedNames::<T>::remove(&name);
+			}
+			Self::deposit_event(Event::NameReservationSet { name, reserved });
 
-			Self::deposit_event(Event::OtpProxyExecuted);
 			Ok(())
 		}
+
+		/// Set or remove the spending limit of a murmur proxy after verifying an OTP
+		/// a limit that allows no more than the current one applies at once and cancels any
+		/// pending limit, while raising or removing the limit only applies after
+		/// `SpendingLimitDelay`, so that the owner can override a limit set with a leaked OTP
+		/// the hash commits to the encoded `("murmur/set_spending_limit", relayer, limit)`
+		/// fees are settled just like for `proxy`
+		///
+		/// * `name`: The uid of the murmur proxy
+		/// * `position`: The position in the MMR of the encrypted OTP code
+		/// * `hash`: A hash to commit to the OTP code and the limit
+		/// * `ciphertext`: The target leaf data (ciphertext)
+		/// * `proof`: A merkle proof the the target leaf is in the registered MMR
+		/// * `limit`: The new spending limit, or `None` to remove it
+		///
+		#[pallet::weight(T::WeightInfo::set_spending_limit(proof.len() as u32))]
+		#[pallet::call_index(7)]
+		pub fn set_spending_limit(
+			origin: OriginFor<T>,
+			name: BoundedVec<u8, ConstU32<32>>,
+			position: u64,
+			hash: Vec<u8>,
+			ciphertext: Vec<u8>,
+			proof: Vec<Vec<u8>>,
+			limit: Option<SpendingLimitOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			let maybe_relayer = Self::ensure_relayer_or_none(origin)?;
+			let name = Self::normalize(&name).ok_or(Error::<T>::InvalidName)?;
+			let weight = T::WeightInfo::set_spending_limit(proof.len() as u32);
+			let proxy_details = Registry::<T>::get(name.clone())
+				.ok_or(Error::<T>::InvalidProxy)?;
+			Self::ensure_valid_limit(&limit)?;
+
+			Self::verify_otp(
+				&name,
+				&proxy_details,
+				position,
+				hash,
+				ciphertext,
+				proof,
+				Self::limit_payload(&maybe_relayer, &limit),
+			)?;
+
+			Self::apply_pending_limit(&name);
+			if Self::is_tighter(&SpendingLimits::<T>::get(&name), &limit) {
+				PendingSpendingLimits::<T>::remove(&name);
+				Self::do_set_spending_limit(&name, limit);
+			} else {
+				let at = frame_system::Pallet::<T>::block_number()
+					.saturating_add(T::SpendingLimitDelay::get());
+				PendingSpendingLimits::<T>::insert(&name, (at, limit.clone()));
+				Self::deposit_event(Event::SpendingLimitScheduled { name: name.clone(), limit, at });
+			}
+
+			Ok(Self::settle_fee(&name, &proxy_details.address, maybe_relayer, weight)?.into())
+		}
+
+		/// Set the guardian of a murmur proxy that `set_guardian` scheduled, once it is due
+		/// anyone can apply it, so that the guardian can recover a proxy whose owner lost its OTPs
+		///
+		/// * `name`: The uid of the murmur proxy
+		///
+		#[pallet::weight(T::WeightInfo::apply_guardian())]
+		#[pallet::call_index(8)]
+		pub fn apply_guardian(
+			origin: OriginFor<T>,
+			name: BoundedVec<u8, ConstU32<32>>,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let name = Self::normalize(&name).ok_or(Error::<T>::InvalidName)?;
+			let proxy_details = Registry::<T>::get(name.clone())
+				.ok_or(Error::<T>::InvalidProxy)?;
+
+			let (guardian, proxy_type, delay) = match PendingGuardians::<T>::get(&name) {
+				Some((at, pending)) if at <= frame_system::Pallet::<T>::block_number() => pending,
+				_ => return Err(Error::<T>::NoDueGuardian.into()),
+			};
+			PendingGuardians::<T>::remove(&name);
+
+			Self::do_set_guardian(&name, &proxy_details.address, Some(guardian), proxy_type, delay)
+		}
 	}
 }
 
-// impl<T: Config> Pallet<T> {
+impl<T: Config> Pallet<T> {
+	/// The canonical form of a name, which is lowercase
+	/// names must be non-empty and consist of ASCII letters, digits, `-` and `_`
+	pub fn normalize(name: &[u8]) -> Option<Name> {
+		let valid = |c: &u8| c.is_ascii_alphanumeric() || *c == b'-' || *c == b'_';
+		if name.is_empty() || !name.iter().all(valid) {
+			return None;
+		}
+		Name::try_from(name.to_ascii_lowercase()).ok()
+	}
+
+	/// The details of the murmur proxy called `name`, if it is registered
+	pub fn proxy_details(name: &[u8]) -> Option<MurmurProxyDetails<T::AccountId>> {
+		Self::normalize(name).and_then(Registry::<T>::get)
+	}
+
+	/// The name of the murmur proxy at `address`, if it is one
+	pub fn name_of(address: &T::AccountId) -> Option<Name> {
+		Names::<T>::get(address)
+	}
+
+	/// The beacon height whose OTP is verified by calls included in the next block
+	pub fn latest() -> BlockNumberFor<T> {
+		T::TlockProvider::latest()
+	}
+
+	/// The deposit for registering `name`
+	pub fn name_deposit(name: &Name) -> BalanceOf<T> {
+		T::NameDepositPerByte::get()
+			.saturating_mul((name.len() as u32).into())
+			.saturating_add(T::NameDepositBase::get())
+	}
+
+	// each payload commits to the relayer of the call, which is repaid by the proxy
+	fn proxy_payload(
+		relayer: &Option<T::AccountId>,
+		call: &<T as pallet_proxy::Config>::RuntimeCall,
+	) -> Vec<u8> {
+		(PROXY_TAG, relayer, call).encode()
+	}
+
+	fn update_root_payload(
+		relayer: &Option<T::AccountId>,
+		new_root: &Vec<u8>,
+		new_size: u64,
+	) -> Vec<u8> {
+		(UPDATE_ROOT_TAG, relayer, new_root, new_size).encode()
+	}
+
+	fn transfer_payload(
+		relayer: &Option<T::AccountId>,
+		new_root: &Vec<u8>,
+		new_size: u64,
+	) -> Vec<u8> {
+		(TRANSFER_TAG, relayer, new_root, new_size).encode()
+	}
+
+	fn close_payload(relayer: &Option<T::AccountId>, dest: &T::AccountId) -> Vec<u8> {
+		(CLOSE_TAG, relayer, dest).encode()
+	}
+
+	fn guardian_payload(
+		relayer: &Option<T::AccountId>,
+		guardian: &Option<T::AccountId>,
+		proxy_type: &T::ProxyType,
+		delay: BlockNumberFor<T>,
+	) -> Vec<u8> {
+		(GUARDIAN_TAG, relayer, guardian, proxy_type, delay).encode()
+	}
 
-	
-// }
\ No newline at end of file
+	fn limit_payload(
+		relayer: &Option<T::AccountId>,
+		limit: &Option<SpendingLimitOf<T>>,
+	) -> Vec<u8> {
+		(LIMIT_TAG, relayer, limit).encode()
+	}
+
+	fn ensure_valid_limit(limit: &Option<SpendingLimitOf<T>>) -> DispatchResult {
+		if let Some(limit) = limit {
+			ensure!(!limit.period.is_zero(), Error::<T>::InvalidSpendingLimit);
+		}
+		Ok(())
+	}
+
+	/// Whether `new` allows a murmur proxy to spend no more than `current`
+	fn is_tighter(current: &Option<SpendingLimitOf<T>>, new: &Option<SpendingLimitOf<T>>) -> bool {
+		match (current, new) {
+			(_, None) => current.is_none(),
+			(None, Some(_)) => true,
+			(Some(current), Some(new)) =>
+				new.amount <= current.amount && new.period >= current.period,
+		}
+	}
+
+	/// The proxy type of the pure proxy of the murmur proxy `name`
+	/// proxies created before storage version 1 were spawned with the default proxy type, which
+	/// was not recorded
+	fn proxy_type_of(name: &Name) -> T::ProxyType {
+		Spawns::<T>::get(name).map_or_else(T::ProxyType::default, |spawn| spawn.proxy_type)
+	}
+
+	/// The spending limit of the murmur proxy `name`, including a pending limit that is due
+	fn spending_limit(name: &Name) -> Option<SpendingLimitOf<T>> {
+		match PendingSpendingLimits::<T>::get(name) {
+			Some((at, limit)) if at <= frame_system::Pallet::<T>::block_number() => limit,
+			_ => SpendingLimits::<T>::get(name),
+		}
+	}
+
+	/// Replace the spending limit of the murmur proxy `name` with its pending limit, if it is due
+	fn apply_pending_limit(name: &Name) {
+		if let Some((at, limit)) = PendingSpendingLimits::<T>::get(name) {
+			if at <= frame_system::Pallet::<T>::block_number() {
+				PendingSpendingLimits::<T>::remove(name);
+				Self::do_set_spending_limit(name, limit);
+			}
+		}
+	}
+
+	/// Replace the spending limit of the murmur proxy `name`
+	/// what it spent in the current period still counts against the new limit
+	fn do_set_spending_limit(name: &Name, limit: Option<SpendingLimitOf<T>>) {
+		match limit.clone() {
+			Some(limit) => SpendingLimits::<T>::insert(name, limit),
+			None => {
+				SpendingLimits::<T>::remove(name);
+				Spent::<T>::remove(name);
+			},
+		}
+		Self::deposit_event(Event::SpendingLimitSet { name: name.clone(), limit });
+	}
+
+	/// The start of the current period of the murmur proxy `name` and what it has spent in it
+	/// after spending `amount`, if it has a spending limit
+	/// fails if that is more than the limit
+	fn check_spend(
+		name: &Name,
+		amount: BalanceOf<T>,
+	) -> Result<Option<(BlockNumberFor<T>, BalanceOf<T>)>, Error<T>> {
+		let limit = match Self::spending_limit(name) {
+			Some(limit) => limit,
+			None => return Ok(None),
+		};
+		let now = frame_system::Pallet::<T>::block_number();
+		let start = now.saturating_sub(now % limit.period);
+		let spent = match Spent::<T>::get(name) {
+			Some((since, spent)) if since == start => spent,
+			_ => Zero::zero(),
+		};
+		let spent = spent.saturating_add(amount);
+		ensure!(spent <= limit.amount, Error::<T>::SpendingLimitExceeded);
+		Ok(Some((start, spent)))
+	}
+
+	/// Kill the pure proxy of the murmur proxy `name` at `address` and sweep its funds to `dest`
+	fn do_close(name: &Name, address: &T::AccountId, dest: T::AccountId) -> DispatchResult {
+		// killing the pure proxy would return the deposit for the guardian to the spawner
+		Self::remove_guardian(name, address)?;
+
+		// proxies created before storage version 1 did not record where they were spawned, so
+		// their pure proxy cannot be killed and is only swept
+		let maybe_spawn = Spawns::<T>::take(name);
+		if let Some(spawn) = &maybe_spawn {
+			pallet_proxy::Pallet::<T>::kill_pure(
+				frame_system::RawOrigin::Signed(address.clone()).into(),
+				T::Lookup::unlookup(spawn.spawner.clone()),
+				spawn.proxy_type.clone(),
+				0,
+				spawn.height,
+				spawn.ext_index,
+			)?;
+		}
+
+		// sweeping the proxy spends its balance, so it must be within its spending limit
+		let balance = <T as pallet_proxy::Config>::Currency::free_balance(address);
+		Self::check_spend(name, balance)?;
+		<T as pallet_proxy::Config>::Currency::transfer(
+			address,
+			&dest,
+			balance,
+			ExistenceRequirement::AllowDeath,
+		)?;
+
+		if let Some(spawn) = maybe_spawn {
+			<T as pallet_proxy::Config>::Currency::unreserve(&spawn.spawner, spawn.deposit);
+		}
+		Names::<T>::remove(address);
+		Registry::<T>::remove(name);
+		LastExecuted::<T>::remove(name);
+		PendingGuardians::<T>::remove(name);
+		SpendingLimits::<T>::remove(name);
+		PendingSpendingLimits::<T>::remove(name);
+		Spent::<T>::remove(name);
+		Self::deposit_event(Event::OtpProxyClosed {
+			name: name.clone(),
+			address: address.clone(),
+			dest,
+		});
+
+		Ok(())
+	}
+
+	/// Replace the guardian of the murmur proxy `name` at `address`
+	/// the guardian cannot be allowed more calls than the murmur proxy itself
+	fn do_set_guardian(
+		name: &Name,
+		address: &T::AccountId,
+		guardian: Option<T::AccountId>,
+		proxy_type: T::ProxyType,
+		delay: BlockNumberFor<T>,
+	) -> DispatchResult {
+		Self::remove_guardian(name, address)?;
+		if let Some(guardian) = guardian.clone() {
+			Self::ensure_valid_guardian(name, &proxy_type, delay)?;
+			pallet_proxy::Pallet::<T>::add_proxy_delegate(
+				address,
+				guardian.clone(),
+				proxy_type.clone(),
+				delay,
+			)?;
+			Guardians::<T>::insert(name, (guardian, proxy_type.clone(), delay));
+		}
+
+		Self::deposit_event(Event::GuardianSet {
+			name: name.clone(),
+			address: address.clone(),
+			guardian,
+			proxy_type,
+			delay,
+		});
+
+		Ok(())
+	}
+
+	/// Check that a guardian of the murmur proxy `name` with `proxy_type` and `delay` can be set
+	fn ensure_valid_guardian(
+		name: &Name,
+		proxy_type: &T::ProxyType,
+		delay: BlockNumberFor<T>,
+	) -> DispatchResult {
+		ensure!(
+			Self::proxy_type_of(name).is_superset(proxy_type),
+			Error::<T>::InvalidGuardianType
+		);
+		ensure!(delay >= T::MinGuardianDelay::get(), Error::<T>::GuardianDelayTooShort);
+		Ok(())
+	}
+
+	/// Remove the guardian of the murmur proxy `name` at `address`, if it has one
+	fn remove_guardian(name: &Name, address: &T::AccountId) -> DispatchResult {
+		if let Some((guardian, proxy_type, delay)) = Guardians::<T>::take(name) {
+			pallet_proxy::Pallet::<T>::remove_proxy_delegate(address, guardian, proxy_type, delay)?;
+		}
+		Ok(())
+	}
+
+	/// Calls of murmur proxies are either submitted by a signed relayer or unsigned
+	fn ensure_relayer_or_none(
+		origin: OriginFor<T>,
+	) -> Result<Option<T::AccountId>, BadOrigin> {
+		match origin.into() {
+			Ok(frame_system::RawOrigin::Signed(relayer)) => Ok(Some(relayer)),
+			Ok(frame_system::RawOrigin::None) => Ok(None),
+			_ => Err(BadOrigin),
+		}
+	}
+
+	/// Settle the fee for `weight` of a verified call of the murmur proxy at `address`
+	/// a signed relayer pays the transaction fee and is repaid up to `MaxRelayerFee`,
+	/// while for unsigned calls the proxy pays the fee itself, or the call fails
+	fn settle_fee(
+		name: &Name,
+		address: &T::AccountId,
+		maybe_relayer: Option<T::AccountId>,
+		weight: Weight,
+	) -> Result<Pays, DispatchError> {
+		let fee = T::WeightToFee::weight_to_fee(&weight);
+		match maybe_relayer {
+			Some(relayer) => {
+				let amount = fee.min(T::MaxRelayerFee::get());
+				// the relayer takes the risk that the proxy cannot repay it
+				if <T as pallet_proxy::Config>::Currency::transfer(
+					address,
+					&relayer,
+					amount,
+					ExistenceRequirement::KeepAlive,
+				).is_ok() {
+					Self::deposit_event(Event::RelayerRepaid { name: name.clone(), relayer, amount });
+				}
+				Ok(Pays::Yes)
+			},
+			None => {
+				Self::charge_fee(address, fee)?;
+				Ok(Pays::No)
+			},
+		}
+	}
+
+	/// Charge `fee` to the murmur proxy at `address`
+	fn charge_fee(address: &T::AccountId, fee: BalanceOf<T>) -> DispatchResult {
+		let imbalance = <T as pallet_proxy::Config>::Currency::withdraw(
+			address,
+			fee,
+			WithdrawReasons::FEE,
+			ExistenceRequirement::KeepAlive,
+		)?;
+		T::OnFeeCharged::on_unbalanced(imbalance);
+		Ok(())
+	}
+
+	/// Validate an unsigned call of a murmur proxy
+	/// the OTP must be valid for the current height and the proxy must be able to pay
+	/// the fee for the call, which its OTP authorizes
+	pub fn validate_unsigned(call: &Call<T>) -> TransactionValidity {
+		let (name, position, hash, ciphertext, proof, payload) = match call {
+			Call::proxy { fail_on_error: true, .. } => return InvalidTransaction::Call.into(),
+			Call::proxy { name, position, hash, ciphertext, proof, call, .. } =>
+				(name, position, hash, ciphertext, proof, Self::proxy_payload(&None, call)),
+			Call::update_root { name, position, hash, ciphertext, proof, new_root, new_size, .. } =>
+				(name, position, hash, ciphertext, proof, Self::update_root_payload(&None, new_root, *new_size)),
+			Call::transfer_name { name, position, hash, ciphertext, proof, new_root, new_size } =>
+				(name, position, hash, ciphertext, proof, Self::transfer_payload(&None, new_root, *new_size)),
+			Call::close { name, position, hash, ciphertext, proof, dest } =>
+				(name, position, hash, ciphertext, proof, Self::close_payload(&None, dest)),
+			Call::set_guardian { name, position, hash, ciphertext, proof, guardian, proxy_type, delay } =>
+				(name, position, hash, ciphertext, proof, Self::guardian_payload(&None, guardian, proxy_type, *delay)),
+			Call::set_spending_limit { name, position, hash, ciphertext, proof, limit } =>
+				(name, position, hash, ciphertext, proof, Self::limit_payload(&None, limit)),
+			_ => return InvalidTransaction::Call.into(),
+		};
+
+		let name = Self::normalize(name).ok_or(InvalidTransaction::Call)?;
+		let proxy_details = Registry::<T>::get(&name).ok_or(InvalidTransaction::Call)?;
+		let when = Self::check_otp(
+			&name,
+			&proxy_details,
+			*position,
+			hash.clone(),
+			ciphertext.clone(),
+			proof.clone(),
+			payload,
+		).map_err(|e| if e == Error::<T>::ReplayedOTP.into() {
+			InvalidTransaction::Stale
+		} else {
+			InvalidTransaction::BadProof
+		})?;
+
+		if let Call::update_root { new_root, new_size, peaks, leaves, .. } = call {
+			ensure!(
+				Self::is_extension(&proxy_details, peaks.clone(), leaves.clone(), new_root, *new_size),
+				InvalidTransaction::Call
+			);
+		}
+		if let Call::proxy { call, .. } = call {
+			Self::check_spend(&name, T::CallSpend::spend(call)).map_err(|_| InvalidTransaction::Call)?;
+		}
+
+		let fee = T::WeightToFee::weight_to_fee(&call.get_dispatch_info().weight);
+		let currency_min = <T as pallet_proxy::Config>::Currency::minimum_balance();
+		ensure!(
+			<T as pallet_proxy::Config>::Currency::free_balance(&proxy_details.address) >=
+				fee.saturating_add(currency_min),
+			InvalidTransaction::Payment
+		);
+
+		ValidTransaction::with_tag_prefix("Murmur")
+			.priority(T::UnsignedPriority::get())
+			// an OTP can only be used once
+			.and_provides((name, when))
+			.longevity(UNSIGNED_LONGEVITY)
+			.propagate(true)
+			.build()
+	}
+
+	/// Verify an OTP with `check_otp` and mark it as used
+	fn verify_otp(
+		name: &Name,
+		proxy_details: &MurmurProxyDetails<T::AccountId>,
+		position: u64,
+		hash: Vec<u8>,
+		ciphertext: Vec<u8>,
+		proof: Vec<Vec<u8>>,
+		payload: Vec<u8>,
+	) -> DispatchResult {
+		let when = Self::check_otp(name, proxy_details, position, hash, ciphertext, proof, payload)?;
+		LastExecuted::<T>::insert(name, when);
+		Self::deposit_event(Event::OtpVerified { name: name.clone(), position, when });
+
+		Ok(())
+	}
+
+	/// Check that `ciphertext` is the leaf for the current height in the proxy's MMR
+	/// and that `hash` commits to its OTP and the `payload`, returning the current height
+	/// the proof is always checked against the size of the MMR registered for the proxy
+	fn check_otp(
+		name: &Name,
+		proxy_details: &MurmurProxyDetails<T::AccountId>,
+		position: u64,
+		hash: Vec<u8>,
+		ciphertext: Vec<u8>,
+		proof: Vec<Vec<u8>>,
+		payload: Vec<u8>,
+	) -> Result<BlockNumberFor<T>, DispatchError> {
+		// the weights are benchmarked with OTPs of these lengths
+		ensure!(
+			hash.len() <= HASH_LENGTH &&
+				ciphertext.len() <= T::MaxCiphertextLen::get() as usize &&
+				proof.iter().all(|item| item.len() <= HASH_LENGTH),
+			Error::<T>::OversizedOtp
+		);
+
+		let when = T::TlockProvider::latest();
+
+		// the OTP is locked to `when`, so it must not be accepted twice
+		ensure!(
+			LastExecuted::<T>::get(name).map_or(true, |last| when > last),
+			Error::<T>::ReplayedOTP
+		);
+
+		let size = proxy_details.size;
+		ensure!(
+			position < size && pos_height_in_tree(position) == 0,
+			Error::<T>::InvalidPosition
+		);
+
+		let leaves: Vec<Leaf> = proof.into_iter()
+			.map(|p| Leaf(p)).collect::<Vec<_>>();
+		let merkle_proof = MerkleProof::<Leaf, MergeLeaves>::new(size, leaves);
+		let root = Leaf(proxy_details.root.to_vec());
+
+		let in_mmr = merkle_proof
+			.verify(root.clone(), vec![(position, Leaf(ciphertext.clone()))])
+			.map_err(|_| Error::<T>::MalformedProof)?;
+		ensure!(in_mmr, Error::<T>::InvalidMerkleProof);
+
+		let result = T::TlockProvider::decrypt_at(&ciphertext, when)
+			.map_err(Error::<T>::from)?;
+		let otp = result.message;
+
+		let validity = murmur::verify(
+			root,
+			merkle_proof,
+			hash,
+			ciphertext,
+			otp,
+			payload,
+			position
+		);
+
+		ensure!(validity, Error::<T>::InvalidOTP);
+
+		Ok(when)
+	}
+
+	/// Check that pushing `leaves` onto the proxy's MMR gives an MMR with `new_root` and `new_size`
+	/// appending to an MMR only needs its peaks, and bagging them must give the current root
+	fn is_extension(
+		proxy_details: &MurmurProxyDetails<T::AccountId>,
+		peaks: Vec<Vec<u8>>,
+		leaves: Vec<Vec<u8>>,
+		new_root: &[u8],
+		new_size: u64,
+	) -> bool {
+		let peak_positions = get_peaks(proxy_details.size);
+		if leaves.is_empty() || peaks.len() != peak_positions.len() {
+			return false;
+		}
+		// the leaves are the ciphertexts of new OTPs
+		let max_leaf = T::MaxCiphertextLen::get() as usize;
+		if peaks.iter().any(|peak| peak.len() > HASH_LENGTH) ||
+			leaves.iter().any(|leaf| leaf.len() > max_leaf)
+		{
+			return false;
+		}
+
+		let store = MemStore::<Leaf>::default();
+		let mut writer = &store;
+		for (pos, peak) in peak_positions.into_iter().zip(peaks) {
+			if writer.append(pos, vec![Leaf(peak)]).is_err() {
+				return false;
+			}
+		}
+
+		let mut mmr = MMR::<Leaf, MergeLeaves, &MemStore<Leaf>>::new(proxy_details.size, &store);
+		if mmr.get_root().ok() != Some(Leaf(proxy_details.root.to_vec())) {
+			return false;
+		}
+		for leaf in leaves {
+			if mmr.push(Leaf(leaf)).is_err() {
+				return false;
+			}
+		}
+
+		mmr.mmr_size() == new_size && mmr.get_root().ok() == Some(Leaf(new_root.to_vec()))
+	}
+}
+
+impl<T: Config> From<TimelockError> for Error<T> {
+	fn from(e: TimelockError) -> Self {
+		match e {
+			TimelockError::MissingPulse => Error::<T>::MissingPulse,
+			TimelockError::MissingRoundKey | TimelockError::WrongKeyEpoch =>
+				Error::<T>::WrongKeyEpoch,
+			_ => Error::<T>::BadCiphertext,
+		}
+	}
+}
\ No newline at end of file
@@ -0,0 +1,179 @@
+/*
+ * Copyright 2024 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Storage migrations for the murmur pallet.
+
+use super::*;
+use frame_support::traits::OnRuntimeUpgrade;
+use sp_std::marker::PhantomData;
+
+#[cfg(feature = "try-runtime")]
+use frame_support::storage::{with_transaction, StoragePrefixedMap};
+#[cfg(feature = "try-runtime")]
+use sp_runtime::{TransactionOutcome, TryRuntimeError};
+
+const LOG_TARGET: &str = "runtime::murmur::migration";
+
+/// The storage layout of the murmur pallet before storage version 1.
+pub mod v0 {
+	use super::*;
+
+	/// The details of a murmur proxy, whose root was unbounded.
+	#[derive(Clone, RuntimeDebug, PartialEq, Eq, Encode, Decode)]
+	pub struct MurmurProxyDetails<AccountId> {
+		pub address: AccountId,
+		pub root: Vec<u8>,
+		pub size: u64,
+	}
+
+	/// The murmur proxies, by their name as it was registered.
+	#[frame_support::storage_alias]
+	pub type Registry<T: Config> = StorageMap<
+		Pallet<T>,
+		Blake2_256,
+		Vec<u8>,
+		MurmurProxyDetails<<T as frame_system::Config>::AccountId>,
+		OptionQuery,
+	>;
+}
+
+pub mod v1 {
+	use super::*;
+
+	/// Migrates the murmur proxies to storage version 1.
+	///
+	/// Before version 1, names were registered as they were given, roots were unbounded and
+	/// proxies could not be looked up by their address. Each proxy is moved to its normalized
+	/// name, its root is bounded to 32 bytes, which keeps the same encoding, and its name is
+	/// recorded in `Names`.
+	///
+	/// The registry is keyed by a hash of the name that cannot be reversed, so `N` must list
+	/// every name registered before the upgrade, as it was registered. A proxy whose name cannot
+	/// be normalized, whose normalized name is already taken, or whose root is longer than 32
+	/// bytes is left in place and reported, since dropping it would lose track of its funds.
+	///
+	/// The migration only runs while the on-chain storage version is 0.
+	pub struct MigrateToV1<T, N>(PhantomData<(T, N)>);
+
+	impl<T: Config, N: Get<Vec<Vec<u8>>>> MigrateToV1<T, N> {
+		/// Move the proxy registered as `raw` to its normalized name, or why it cannot be moved.
+		fn migrate(raw: &[u8]) -> Result<(), &'static str> {
+			let old = v0::Registry::<T>::get(raw).ok_or("it is not registered")?;
+			let root = Root::try_from(old.root).map_err(|_| "its root is longer than 32 bytes")?;
+			let name = Pallet::<T>::normalize(raw).ok_or("its name cannot be normalized")?;
+			if name.as_slice() != raw {
+				ensure!(!Registry::<T>::contains_key(&name), "its normalized name is taken");
+				v0::Registry::<T>::remove(raw);
+			}
+			Names::<T>::insert(&old.address, &name);
+			Registry::<T>::insert(
+				&name,
+				MurmurProxyDetails { address: old.address, root, size: old.size },
+			);
+			Ok(())
+		}
+	}
+
+	impl<T: Config, N: Get<Vec<Vec<u8>>>> OnRuntimeUpgrade for MigrateToV1<T, N> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version != 0 {
+				log::info!(
+					target: LOG_TARGET,
+					"skipping the v1 migration, the on-chain storage version is {:?}",
+					on_chain_version,
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let names = N::get();
+			let mut migrated = 0u64;
+			for raw in names.iter() {
+				match Self::migrate(raw) {
+					Ok(()) => migrated.saturating_inc(),
+					Err(reason) => log::error!(
+						target: LOG_TARGET,
+						"the murmur proxy {:?} was not migrated, {}",
+						raw,
+						reason,
+					),
+				}
+			}
+			StorageVersion::new(1).put::<Pallet<T>>();
+			log::info!(
+				target: LOG_TARGET,
+				"migrated {} of {} murmur proxies to v1",
+				migrated,
+				names.len(),
+			);
+
+			let count = names.len() as u64;
+			T::DbWeight::get().reads_writes(2 * count + 1, 3 * count + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 0,
+				"the murmur pallet is not at storage version 0"
+			);
+			let proxies = v0::Registry::<T>::iter_values().count() as u32;
+			ensure!(
+				N::get().iter().filter(|raw| v0::Registry::<T>::contains_key(raw)).count() as u32 ==
+					proxies,
+				"a murmur proxy is not listed by its name"
+			);
+			// a dry run finds the proxies that are left in place, since whether a name is taken
+			// depends on the names migrated before it
+			let skipped: Vec<Vec<u8>> = with_transaction(|| {
+				let skipped =
+					N::get().into_iter().filter(|raw| Self::migrate(raw).is_err()).collect();
+				TransactionOutcome::Rollback(Ok::<_, TryRuntimeError>(skipped))
+			})?;
+			Ok((proxies, skipped).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let (proxies, skipped): (u32, Vec<Vec<u8>>) = Decode::decode(&mut &state[..])
+				.map_err(|_| "the pre-upgrade state cannot be decoded")?;
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 1,
+				"the murmur pallet was not moved to storage version 1"
+			);
+			// the proxies left in place keep their old value, which cannot be decoded as a v1
+			// value if its root is too long, so `iter_values` skips them
+			let unreadable = skipped
+				.iter()
+				.filter_map(|raw| v0::Registry::<T>::get(raw))
+				.filter(|old| Root::try_from(old.root.clone()).is_err())
+				.count() as u32;
+			ensure!(
+				Registry::<T>::iter_values().count() as u32 + unreadable == proxies,
+				"a murmur proxy was lost in the migration"
+			);
+			for raw in N::get().into_iter().filter(|raw| !skipped.contains(raw)) {
+				let details = Pallet::<T>::proxy_details(&raw)
+					.ok_or("a murmur proxy cannot be found by its name")?;
+				ensure!(
+					Pallet::<T>::name_of(&details.address) == Pallet::<T>::normalize(&raw),
+					"a murmur proxy cannot be found by its address"
+				);
+			}
+			Ok(())
+		}
+	}
+}
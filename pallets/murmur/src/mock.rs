@@ -21,9 +21,12 @@ use codec::Encode;
 use frame_support::{
 	construct_runtime, derive_impl, parameter_types,
 	traits::{ConstU128, ConstU32, ConstU64, Contains, InstanceFilter},
+	weights::FixedFee,
 };
 use frame_system::Call as SystemCall;
-use sp_consensus_beefy_etf::{mmr::MmrLeafVersion, test_utils::etf_genesis};
+use sp_consensus_beefy_etf::{
+	known_payloads, mmr::MmrLeafVersion, test_utils::etf_genesis, Commitment, Payload,
+};
 use sp_core::Pair;
 use sp_io::TestExternalities;
 use sp_runtime::{
@@ -33,7 +36,10 @@ use sp_runtime::{
 	traits::{BlakeTwo256, ConvertInto, Keccak256, OpaqueKeys},
 	BuildStorage,
 };
-use murmur_test_utils::BOTPGenerator;
+use murmur_core::types::{Identity, IdentityBuilder};
+use murmur_test_utils::{BOTPGenerator, MurmurStore};
+use ckb_merkle_mountain_range::helper::leaf_index_to_mmr_size;
+use w3f_bls::{DoublePublicKey, SerializableToBytes, TinyBLS377};
 use sp_state_machine::BasicExternalities;
 use etf_crypto_primitives::encryption::tlock::DecryptionResult;
 use sha3::Digest;
@@ -108,8 +114,14 @@ impl pallet_mmr::Config for Test {
 }
 
 impl pallet_etf::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
 	type BeefyId = BeefyId;
 	type MaxAuthorities = ConstU32<100>;
+	type ShareVerifier = pallet_etf::AcssShareVerifier<w3f_bls::TinyBLS377>;
+	type UpdateOrigin = frame_system::EnsureRoot<u64>;
+	type ValidatorSet = Session;
+	type BeefyValidatorSetId = pallet_beefy_etf::CurrentValidatorSetId<Test>;
+	type WeightInfo = ();
 }
 
 impl pallet_beefy_etf::Config for Test {
@@ -194,10 +206,82 @@ impl TimelockEncryptionProvider<u64> for DummyTlockProvider {
 	}
 }
 
+#[derive(Debug)]
+pub struct BasicIdBuilder;
+impl IdentityBuilder<BlockNumber> for BasicIdBuilder {
+	fn build_identity(at: BlockNumber) -> Identity {
+		let payload = Payload::from_single_entry(known_payloads::ETF_SIGNATURE, Vec::new());
+		let commitment = Commitment {
+			payload,
+			block_number: at,
+			validator_set_id: 0, /* TODO: how to ensure correct validator set ID is used? could
+			                      * just always set to 1 for now, else set input param. */
+		};
+		Identity::new(&commitment.encode())
+	}
+}
+
+/// The MMR of the OTPs for heights 1 to `2^p` of the seed the mock beacon decrypts with
+#[cfg(feature = "runtime-benchmarks")]
+fn benchmark_store(p: u32) -> MurmurStore {
+	let round_pubkey_bytes = Etf::round_pubkey().to_vec();
+	let round_pubkey = DoublePublicKey::<TinyBLS377>::from_bytes(&round_pubkey_bytes).unwrap();
+	MurmurStore::new::<TinyBLS377, BasicIdBuilder>(
+		b"seed".to_vec().into(),
+		(1..=(1 << p)).collect(),
+		[3; 32],
+		round_pubkey,
+	)
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl BenchmarkHelper for MockBenchmarkHelper {
+	fn mmr(p: u32) -> (Vec<u8>, u64) {
+		(benchmark_store(p).root.0.to_vec(), leaf_index_to_mmr_size((1 << p) - 1))
+	}
+
+	// the mock beacon can decrypt the OTP of any height
+	fn otp_proof(p: u32, payload: Vec<u8>) -> OtpProof {
+		let (proof, hash, ciphertext, position) = benchmark_store(p)
+			.execute(b"seed".to_vec(), DummyTlockProvider::latest() as u32, payload)
+			.unwrap();
+		let proof = proof.proof_items().iter().map(|leaf| leaf.0.to_vec()).collect();
+		OtpProof { position, hash, ciphertext, proof }
+	}
+}
+
 impl pallet_murmur::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
+	type WeightInfo = ();
 	type TlockProvider = DummyTlockProvider;
+	type WeightToFee = FixedFee<1_000, u128>;
+	type OnFeeCharged = ();
+	type MaxRelayerFee = ConstU128<500>;
+	type UnsignedPriority = ConstU64<{ u64::MAX }>;
+	type NameDepositBase = ConstU128<10>;
+	type NameDepositPerByte = ConstU128<2>;
+	type ReserveNameOrigin = frame_system::EnsureRoot<u64>;
+	type CallSpend = TransferredValue;
+	type SpendingLimitDelay = ConstU64<10>;
+	type MinGuardianDelay = ConstU64<5>;
+	type MaxCiphertextLen = ConstU32<1024>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockBenchmarkHelper;
+}
+
+/// Balance transfers spend the value they transfer
+pub struct TransferredValue;
+impl CallSpend<RuntimeCall, u128> for TransferredValue {
+	fn spend(call: &RuntimeCall) -> u128 {
+		match call {
+			RuntimeCall::Balances(pallet_balances::Call::transfer_allow_death { value, .. }) |
+			RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive { value, .. }) => *value,
+			_ => 0,
+		}
+	}
 }
 
 #[derive(
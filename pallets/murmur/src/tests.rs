@@ -3,9 +3,11 @@ use ark_serialize::CanonicalSerialize;
 use ark_std::{test_rng, UniformRand};
 use frame_support::{
 	assert_noop, assert_ok, BoundedVec,
+	dispatch::{GetDispatchInfo, Pays},
 	traits::{
 		ConstU32,
 		OnInitialize,
+		UnfilteredDispatchable,
 	},
 };
 use frame_system::Call as SystemCall;
@@ -19,9 +21,14 @@ use murmur_test_utils::{
     MurmurStore
 };
 use sp_core::{bls377, Pair, ByteArray};
+use sp_runtime::{
+	traits::{BadOrigin, ValidateUnsigned},
+	transaction_validity::{InvalidTransaction, TransactionSource},
+};
 use ckb_merkle_mountain_range::{
+	helper::{get_peaks, leaf_index_to_mmr_size},
 	util::{MemMMR, MemStore},
-	MerkleProof,
+	MerkleProof, MMRStore, MMR,
 };
 
 use codec::{Decode, Encode};
@@ -38,21 +45,6 @@ use w3f_bls::{
 	TinyBLS377
 };
 
-#[derive(Debug)]
-pub struct BasicIdBuilder;
-impl IdentityBuilder<BlockNumber> for BasicIdBuilder {
-	fn build_identity(at: BlockNumber) -> Identity {
-		let payload = Payload::from_single_entry(known_payloads::ETF_SIGNATURE, Vec::new());
-		let commitment = Commitment {
-			payload,
-			block_number: at,
-			validator_set_id: 0, /* TODO: how to ensure correct validator set ID is used? could
-			                      * just always set to 1 for now, else set input param. */
-		};
-		Identity::new(&commitment.encode())
-	}
-}
-
 #[test]
 fn it_can_create_new_proxy_with_unique_name() {
 	let seed = b"seed".to_vec();
@@ -61,7 +53,8 @@ fn it_can_create_new_proxy_with_unique_name() {
 	let block_schedule = vec![1, 2, 3];
 	let ephem_msk = [3; 32];
 
-	let size = 3;
+	// the MMR of three leaves has four nodes
+	let size = leaf_index_to_mmr_size(2);
 
 	new_test_ext(vec![0]).execute_with(|| {
 		let round_pubkey_bytes = Etf::round_pubkey().to_vec();
@@ -79,6 +72,8 @@ fn it_can_create_new_proxy_with_unique_name() {
 			root.0.to_vec(),
 			size,
 			bounded_name.clone(),
+			ProxyType::Any,
+			None,
 		));
 
 		// check storage
@@ -100,7 +95,8 @@ fn it_can_proxy_valid_calls() {
 	let when = 1;
 	let block_schedule = vec![1, 2, 3];
 	let ephem_msk = [3; 32];
-	let size = 3;
+	// the MMR of three leaves has four nodes
+	let size = leaf_index_to_mmr_size(2);
 
 	new_test_ext(vec![0]).execute_with(|| {
 		let round_pubkey_bytes = Etf::round_pubkey().to_vec();
@@ -119,6 +115,8 @@ fn it_can_proxy_valid_calls() {
 			root.0.to_vec(),
 			size,
 			bounded_name.clone(),
+			ProxyType::Any,
+			None,
 		));
 
 		// the beacon would write a new pulse here, but we will mock it instead
@@ -130,7 +128,7 @@ fn it_can_proxy_valid_calls() {
 		let (proof, commitment, ciphertext, pos) = mmr_store.execute(
 			seed.clone(),
 			when.clone() as u32,
-			call.encode().to_vec(),
+			(crate::PROXY_TAG, Some(0u64), &call).encode(),
 		).unwrap(); 
 
 		let proof_items: Vec<Vec<u8>> = proof.proof_items().iter()
@@ -145,8 +143,1295 @@ fn it_can_proxy_valid_calls() {
 			ciphertext,
 			proof_items,
 			Box::new(call),
+			false,
+		));
+
+	});
+}
+
+#[test]
+fn it_rejects_a_replayed_otp() {
+	let seed = b"seed".to_vec();
+	let bounded_name = BoundedVec::<u8, ConstU32<32>>::truncate_from(b"name".to_vec());
+	let when = 1;
+	let block_schedule = vec![1, 2, 3];
+	let ephem_msk = [3; 32];
+	// the MMR of three leaves has four nodes
+	let size = leaf_index_to_mmr_size(2);
+
+	new_test_ext(vec![0]).execute_with(|| {
+		let round_pubkey_bytes = Etf::round_pubkey().to_vec();
+		let round_pubkey = DoublePublicKey::<TinyBLS377>::from_bytes(&round_pubkey_bytes).unwrap();
+		let mmr_store = MurmurStore::new::<TinyBLS377, BasicIdBuilder>(
+			seed.clone().into(),
+			block_schedule.clone(),
+			ephem_msk,
+			round_pubkey,
+		);
+		assert_ok!(Murmur::create(
+			RuntimeOrigin::signed(0),
+			mmr_store.root.0.to_vec(),
+			size,
+			bounded_name.clone(),
+			ProxyType::Any,
+			None,
+		));
+
+		let call = call_remark(vec![1, 2, 3, 4, 5]);
+		let (proof, commitment, ciphertext, pos) = mmr_store.execute(
+			seed.clone(),
+			when as u32,
+			(crate::PROXY_TAG, Some(0u64), &call).encode(),
+		).unwrap();
+		let proof_items: Vec<Vec<u8>> = proof.proof_items().iter()
+			.map(|leaf| leaf.0.to_vec())
+			.collect::<Vec<_>>();
+
+		assert_ok!(Murmur::proxy(
+			RuntimeOrigin::signed(0),
+			bounded_name.clone(),
+			pos,
+			commitment.clone(),
+			ciphertext.clone(),
+			proof_items.clone(),
+			Box::new(call.clone()),
+			false,
+		));
+		assert_eq!(murmur::LastExecuted::<Test>::get(bounded_name.clone()), Some(when));
+
+		// anyone who saw the first call cannot submit it again
+		assert_noop!(
+			Murmur::proxy(
+				RuntimeOrigin::signed(1),
+				bounded_name.clone(),
+				pos,
+				commitment,
+				ciphertext,
+				proof_items,
+				Box::new(call),
+				false,
+			),
+			Error::<Test>::ReplayedOTP,
+		);
+	});
+}
+
+#[test]
+fn it_rejects_an_oversized_otp() {
+	let seed = b"seed".to_vec();
+	let bounded_name = BoundedVec::<u8, ConstU32<32>>::truncate_from(b"name".to_vec());
+	let when = 1;
+	let ephem_msk = [3; 32];
+	let size = leaf_index_to_mmr_size(2);
+
+	new_test_ext(vec![0]).execute_with(|| {
+		let round_pubkey_bytes = Etf::round_pubkey().to_vec();
+		let round_pubkey = DoublePublicKey::<TinyBLS377>::from_bytes(&round_pubkey_bytes).unwrap();
+		let mmr_store = MurmurStore::new::<TinyBLS377, BasicIdBuilder>(
+			seed.clone().into(),
+			vec![1, 2, 3],
+			ephem_msk,
+			round_pubkey,
+		);
+		assert_ok!(Murmur::create(
+			RuntimeOrigin::signed(0),
+			mmr_store.root.0.to_vec(),
+			size,
+			bounded_name.clone(),
+			ProxyType::Any,
+			None,
+		));
+
+		let call = call_remark(vec![1, 2, 3, 4, 5]);
+		let (proof, commitment, ciphertext, pos) = mmr_store.execute(
+			seed.clone(),
+			when as u32,
+			(crate::PROXY_TAG, Some(0u64), &call).encode(),
+		).unwrap();
+		let proof_items: Vec<Vec<u8>> = proof.proof_items().iter()
+			.map(|leaf| leaf.0.to_vec())
+			.collect::<Vec<_>>();
+
+		let mut long_hash = commitment.clone();
+		long_hash.resize(33, 0);
+		let mut long_ciphertext = ciphertext.clone();
+		long_ciphertext.resize(1025, 0);
+		let mut long_proof = proof_items.clone();
+		long_proof[0].resize(33, 0);
+		for (hash, ciphertext, proof) in [
+			(long_hash, ciphertext.clone(), proof_items.clone()),
+			(commitment.clone(), long_ciphertext, proof_items.clone()),
+			(commitment.clone(), ciphertext.clone(), long_proof),
+		] {
+			assert_noop!(
+				Murmur::proxy(
+					RuntimeOrigin::signed(0),
+					bounded_name.clone(),
+					pos,
+					hash,
+					ciphertext,
+					proof,
+					Box::new(call.clone()),
+					false,
+				),
+				Error::<Test>::OversizedOtp,
+			);
+		}
+
+		assert_ok!(Murmur::proxy(
+			RuntimeOrigin::signed(0),
+			bounded_name,
+			pos,
+			commitment,
+			ciphertext,
+			proof_items,
+			Box::new(call),
+			false,
+		));
+	});
+}
+
+#[test]
+fn it_can_extend_the_mmr_of_a_proxy() {
+	let seed = b"seed".to_vec();
+	let bounded_name = BoundedVec::<u8, ConstU32<32>>::truncate_from(b"name".to_vec());
+	let when = 1;
+	let ephem_msk = [3; 32];
+
+	new_test_ext(vec![0]).execute_with(|| {
+		let round_pubkey_bytes = Etf::round_pubkey().to_vec();
+		let round_pubkey = DoublePublicKey::<TinyBLS377>::from_bytes(&round_pubkey_bytes).unwrap();
+		let mmr_store = MurmurStore::new::<TinyBLS377, BasicIdBuilder>(
+			seed.clone().into(),
+			vec![1, 2, 3],
+			ephem_msk,
+			round_pubkey,
+		);
+		// the same schedule with two more heights appended
+		let extended_store = MurmurStore::new::<TinyBLS377, BasicIdBuilder>(
+			seed.clone().into(),
+			vec![1, 2, 3, 4, 5],
+			ephem_msk,
+			round_pubkey,
+		);
+
+		System::set_block_number(1);
+
+		// rebuild the current MMR to read its peaks
+		let store = MemStore::<Leaf>::default();
+		let mut mmr = MMR::<Leaf, MergeLeaves, &MemStore<Leaf>>::new(0, &store);
+		for leaf in mmr_store.metadata.values() {
+			mmr.push(Leaf(leaf.clone())).unwrap();
+		}
+		mmr.commit().unwrap();
+		let size = leaf_index_to_mmr_size(2);
+		let peaks: Vec<Vec<u8>> = get_peaks(size).into_iter()
+			.map(|pos| (&store).get_elem(pos).unwrap().unwrap().0)
+			.collect();
+		let leaves: Vec<Vec<u8>> = extended_store.metadata.iter()
+			.filter(|(block, _)| **block > 3)
+			.map(|(_, leaf)| leaf.clone())
+			.collect();
+
+		assert_ok!(Murmur::create(
+			RuntimeOrigin::signed(0),
+			mmr_store.root.0.to_vec(),
+			size,
+			bounded_name.clone(),
+			ProxyType::Any,
+			None,
+		));
+
+		let new_root = extended_store.root.0.to_vec();
+		let new_size = leaf_index_to_mmr_size(4);
+		let (proof, commitment, ciphertext, pos) = mmr_store.execute(
+			seed.clone(),
+			when as u32,
+			(crate::UPDATE_ROOT_TAG, Some(0u64), new_root.clone(), new_size).encode(),
+		).unwrap();
+		let proof_items: Vec<Vec<u8>> = proof.proof_items().iter()
+			.map(|leaf| leaf.0.to_vec())
+			.collect::<Vec<_>>();
+
+		// the new root must be reachable from the current peaks
+		assert_noop!(
+			Murmur::update_root(
+				RuntimeOrigin::signed(0),
+				bounded_name.clone(),
+				pos,
+				commitment.clone(),
+				ciphertext.clone(),
+				proof_items.clone(),
+				new_root.clone(),
+				new_size,
+				peaks.clone(),
+				leaves.iter().rev().cloned().collect(),
+			),
+			Error::<Test>::InvalidExtension,
+		);
+
+		assert_ok!(Murmur::update_root(
+			RuntimeOrigin::signed(0),
+			bounded_name.clone(),
+			pos,
+			commitment,
+			ciphertext,
+			proof_items,
+			new_root.clone(),
+			new_size,
+			peaks,
+			leaves,
+		));
+
+		let details = murmur::Registry::<Test>::get(bounded_name.clone()).unwrap();
+		assert_eq!(details.root, new_root);
+		assert_eq!(details.size, new_size);
+		System::assert_has_event(
+			murmur::Event::OtpProxyRootUpdated { name: bounded_name.clone(), address: details.address }
+				.into(),
+		);
+		assert_eq!(murmur::LastExecuted::<Test>::get(bounded_name), Some(when));
+	});
+}
+
+#[test]
+fn it_verifies_proofs_against_the_registered_size() {
+	let seed = b"seed".to_vec();
+	let when = 1;
+	let ephem_msk = [3; 32];
+
+	new_test_ext(vec![0]).execute_with(|| {
+		let round_pubkey_bytes = Etf::round_pubkey().to_vec();
+		let round_pubkey = DoublePublicKey::<TinyBLS377>::from_bytes(&round_pubkey_bytes).unwrap();
+		let mmr_store = MurmurStore::new::<TinyBLS377, BasicIdBuilder>(
+			seed.clone().into(),
+			vec![1, 2, 3],
+			ephem_msk,
+			round_pubkey,
+		);
+		let size = leaf_index_to_mmr_size(2);
+		let call = call_remark(vec![1, 2, 3, 4, 5]);
+		let (proof, commitment, ciphertext, pos) = mmr_store.execute(
+			seed.clone(),
+			when as u32,
+			(crate::PROXY_TAG, Some(0u64), &call).encode(),
+		).unwrap();
+		let proof_items: Vec<Vec<u8>> = proof.proof_items().iter()
+			.map(|leaf| leaf.0.to_vec())
+			.collect::<Vec<_>>();
+
+		let proxy = |name: &murmur::Name, position: u64| Murmur::proxy(
+			RuntimeOrigin::signed(0),
+			name.clone(),
+			position,
+			commitment.clone(),
+			ciphertext.clone(),
+			proof_items.clone(),
+			Box::new(call.clone()),
+			false,
+		);
+		let is_proof_error = |e: sp_runtime::DispatchError| [
+			Error::<Test>::InvalidPosition.into(),
+			Error::<Test>::MalformedProof.into(),
+			Error::<Test>::InvalidMerkleProof.into(),
+		].contains(&e);
+
+		System::set_block_number(1);
+		let name = BoundedVec::truncate_from(b"name".to_vec());
+		assert_ok!(Murmur::create(
+			RuntimeOrigin::signed(0),
+			mmr_store.root.0.to_vec(),
+			size,
+			name.clone(),
+			ProxyType::Any,
+			None,
+		));
+
+		// the registered size is used, so a wallet registered with any other size
+		// rejects the valid proof
+		for wrong_size in (0..32u64).filter(|s| *s != size) {
+			murmur::Registry::<Test>::mutate(&name, |details| {
+				details.as_mut().unwrap().size = wrong_size;
+			});
+			let e = proxy(&name, pos).unwrap_err();
+			assert!(is_proof_error(e), "size {} gave {:?}", wrong_size, e);
+		}
+		murmur::Registry::<Test>::mutate(&name, |details| details.as_mut().unwrap().size = size);
+
+		// the registered size accepts no other position
+		for position in (0..64u64).chain([u64::MAX]).filter(|p| *p != pos) {
+			let e = proxy(&name, position).unwrap_err();
+			assert!(is_proof_error(e), "position {} gave {:?}", position, e);
+		}
+		assert_noop!(proxy(&name, size), Error::<Test>::InvalidPosition);
+		assert_eq!(murmur::LastExecuted::<Test>::get(name.clone()), None);
+
+		assert_ok!(proxy(&name, pos));
+		System::assert_has_event(
+			murmur::Event::OtpVerified { name, position: pos, when }.into()
+		);
+	});
+}
+
+/// Create a murmur wallet called `name` whose MMR covers heights 1 to 3
+fn create_wallet(name: &[u8]) -> (murmur::Name, MurmurStore) {
+	create_wallet_with(name, ProxyType::Any, None)
+}
+
+/// Create a murmur wallet like `create_wallet`, with a proxy type and spending limit
+fn create_wallet_with(
+	name: &[u8],
+	proxy_type: ProxyType,
+	limit: Option<murmur::SpendingLimitOf<Test>>,
+) -> (murmur::Name, MurmurStore) {
+	let name = BoundedVec::truncate_from(name.to_vec());
+	let round_pubkey_bytes = Etf::round_pubkey().to_vec();
+	let round_pubkey = DoublePublicKey::<TinyBLS377>::from_bytes(&round_pubkey_bytes).unwrap();
+	let mmr_store = MurmurStore::new::<TinyBLS377, BasicIdBuilder>(
+		b"seed".to_vec().into(),
+		vec![1, 2, 3],
+		[3; 32],
+		round_pubkey,
+	);
+	assert_ok!(Murmur::create(
+		RuntimeOrigin::signed(0),
+		mmr_store.root.0.to_vec(),
+		leaf_index_to_mmr_size(2),
+		name.clone(),
+		proxy_type,
+		limit,
+	));
+	(name, mmr_store)
+}
+
+/// The position, hash, ciphertext and proof of the OTP for height 1 committing to `payload`
+fn otp_for(mmr_store: &MurmurStore, payload: Vec<u8>) -> (u64, Vec<u8>, Vec<u8>, Vec<Vec<u8>>) {
+	let (proof, commitment, ciphertext, pos) = mmr_store.execute(
+		b"seed".to_vec(),
+		1,
+		payload,
+	).unwrap();
+	let proof_items = proof.proof_items().iter().map(|leaf| leaf.0.to_vec()).collect();
+	(pos, commitment, ciphertext, proof_items)
+}
+
+#[test]
+fn a_relayer_is_repaid_by_the_proxy() {
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, mmr_store) = create_wallet(b"name");
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+
+		let call = call_remark(vec![1, 2, 3, 4, 5]);
+		let (position, hash, ciphertext, proof) =
+			otp_for(&mmr_store, (crate::PROXY_TAG, Some(1u64), &call).encode());
+		let expected_weight = <() as murmur::WeightInfo>::proxy(proof.len() as u32)
+			.saturating_add(call.get_dispatch_info().weight);
+
+		// the OTP is bound to the relayer, so no one else can claim the repayment
+		let e = Murmur::proxy(
+			RuntimeOrigin::signed(2),
+			name.clone(),
+			position,
+			hash.clone(),
+			ciphertext.clone(),
+			proof.clone(),
+			Box::new(call.clone()),
+			false,
+		).unwrap_err();
+		assert_eq!(e.error, Error::<Test>::InvalidOTP.into());
+
+		let post_info = Murmur::proxy(
+			RuntimeOrigin::signed(1),
+			name.clone(),
+			position,
+			hash,
+			ciphertext,
+			proof,
+			Box::new(call),
+			false,
+		).unwrap();
+
+		// the relayer pays for the transaction and the mock fee of 1_000 is capped at 500
+		assert_eq!(post_info.pays_fee, Pays::Yes);
+		assert_eq!(post_info.actual_weight, Some(expected_weight));
+		assert_eq!(Balances::free_balance(1), 500);
+		assert_eq!(Balances::free_balance(address), 9_500);
+		System::assert_has_event(
+			murmur::Event::RelayerRepaid { name, relayer: 1, amount: 500 }.into()
+		);
+	});
+}
+
+#[test]
+fn an_unsigned_call_is_paid_for_by_the_proxy() {
+	new_test_ext(vec![0]).execute_with(|| {
+		let (name, mmr_store) = create_wallet(b"name");
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		let remark = call_remark(vec![1, 2, 3, 4, 5]);
+		let (position, hash, ciphertext, proof) =
+			otp_for(&mmr_store, (crate::PROXY_TAG, None::<u64>, &remark).encode());
+		let call = murmur::Call::<Test>::proxy {
+			name: name.clone(),
+			position,
+			hash: hash.clone(),
+			ciphertext: ciphertext.clone(),
+			proof: proof.clone(),
+			call: Box::new(remark),
+			fail_on_error: false,
+		};
+
+		// the proxy cannot pay for the call yet
+		assert_eq!(
+			<Murmur as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Payment.into(),
+		);
+		// nor is it dispatched without the fee
+		assert!(call.clone().dispatch_bypass_filter(RuntimeOrigin::none()).is_err());
+		assert!(murmur::LastExecuted::<Test>::get(&name).is_none());
+
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+		assert_ok!(<Murmur as ValidateUnsigned>::validate_unsigned(
+			TransactionSource::External,
+			&call,
+		));
+
+		// an OTP for another call does not authorize the fee
+		let forged = murmur::Call::proxy {
+			name,
+			position,
+			hash,
+			ciphertext,
+			proof,
+			call: Box::new(call_remark(vec![6])),
+			fail_on_error: false,
+		};
+		assert_eq!(
+			<Murmur as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &forged),
+			InvalidTransaction::BadProof.into(),
+		);
+
+		let post_info = call.clone().dispatch_bypass_filter(RuntimeOrigin::none()).unwrap();
+		assert_eq!(post_info.pays_fee, Pays::No);
+		assert_eq!(Balances::free_balance(address), 9_000);
+
+		// the OTP was used
+		assert_eq!(
+			<Murmur as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Stale.into(),
+		);
+	});
+}
+
+#[test]
+fn an_otp_for_an_untagged_call_is_rejected() {
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, mmr_store) = create_wallet(b"name");
+		let call = call_remark(vec![1, 2, 3, 4, 5]);
+		let (position, hash, ciphertext, proof) = otp_for(&mmr_store, call.encode());
+
+		let e = Murmur::proxy(
+			RuntimeOrigin::signed(1),
+			name.clone(),
+			position,
+			hash,
+			ciphertext,
+			proof,
+			Box::new(call),
+			false,
+		)
+		.unwrap_err();
+		assert_eq!(e.error, Error::<Test>::InvalidOTP.into());
+		assert!(murmur::LastExecuted::<Test>::get(name).is_none());
+	});
+}
+
+#[test]
+fn a_closed_proxy_is_killed_and_swept() {
+	let dest = 5;
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, mmr_store) = create_wallet(b"name");
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+		let spawner_reserve = Balances::reserved_balance(0);
+		assert!(spawner_reserve > 0);
+
+		let (position, hash, ciphertext, proof) =
+			otp_for(&mmr_store, (crate::CLOSE_TAG, Some(1u64), dest).encode());
+
+		// an OTP authorizing a close cannot hand the proxy over instead
+		assert_noop!(
+			Murmur::transfer_name(
+				RuntimeOrigin::signed(1),
+				name.clone(),
+				position,
+				hash.clone(),
+				ciphertext.clone(),
+				proof.clone(),
+				vec![1; 32],
+				1,
+			),
+			Error::<Test>::InvalidOTP,
+		);
+
+		assert_ok!(Murmur::close(
+			RuntimeOrigin::signed(1),
+			name.clone(),
+			position,
+			hash,
+			ciphertext,
+			proof,
+			dest,
+		));
+
+		// the relayer was repaid before the rest was swept
+		assert_eq!(Balances::free_balance(1), 500);
+		assert_eq!(Balances::free_balance(dest), 9_500);
+		assert_eq!(Balances::reserved_balance(0), 0);
+		assert!(pallet_proxy::Proxies::<Test>::get(address).0.is_empty());
+		assert!(murmur::Registry::<Test>::get(name.clone()).is_none());
+		assert!(murmur::LastExecuted::<Test>::get(name.clone()).is_none());
+		assert_eq!(Murmur::name_of(&address), None);
+		System::assert_last_event(
+			murmur::Event::OtpProxyClosed { name: name.clone(), address, dest }.into()
+		);
+
+		// the name is released
+		assert_ok!(Murmur::create(
+			RuntimeOrigin::signed(0),
+			mmr_store.root.0.to_vec(),
+			leaf_index_to_mmr_size(2),
+			name,
+			ProxyType::Any,
+			None,
+		));
+	});
+}
+
+#[test]
+fn a_proxy_can_only_be_closed_within_its_spending_limit() {
+	let limit = murmur::SpendingLimit { amount: 1_000, period: 10 };
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, mmr_store) = create_wallet_with(b"name", ProxyType::Any, Some(limit));
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+
+		let close = || {
+			let (position, hash, ciphertext, proof) =
+				otp_for(&mmr_store, (crate::CLOSE_TAG, Some(1u64), 5u64).encode());
+			Murmur::close(RuntimeOrigin::signed(1), name.clone(), position, hash, ciphertext, proof, 5)
+		};
+
+		// a leaked OTP cannot sweep more than the proxy can spend
+		let e = close().unwrap_err();
+		assert_eq!(e.error, Error::<Test>::SpendingLimitExceeded.into());
+		assert_eq!(Balances::free_balance(5), 0);
+		assert!(murmur::Registry::<Test>::get(name.clone()).is_some());
+
+		// the rest of the balance is within the limit once the relayer is repaid
+		assert_ok!(Balances::transfer_allow_death(
+			RuntimeOrigin::signed(address),
+			0,
+			Balances::free_balance(address) - 1_400,
+		));
+		assert_ok!(close());
+		assert_eq!(Balances::free_balance(5), 900);
+		assert!(murmur::Registry::<Test>::get(name).is_none());
+	});
+}
+
+#[test]
+fn a_guardian_is_a_delayed_delegate_of_the_proxy() {
+	let guardian = 7;
+	let delay = 10;
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, mmr_store) = create_wallet(b"name");
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+
+		let payload =
+			(crate::GUARDIAN_TAG, Some(1u64), Some(guardian), ProxyType::JustTransfer, delay).encode();
+		let (position, hash, ciphertext, proof) = otp_for(&mmr_store, payload);
+		assert_ok!(Murmur::set_guardian(
+			RuntimeOrigin::signed(1),
+			name.clone(),
+			position,
+			hash,
+			ciphertext,
+			proof,
+			Some(guardian),
+			ProxyType::JustTransfer,
+			delay,
+		));
+
+		// the guardian can only be set once the owner had time to remove it
+		assert!(murmur::Guardians::<Test>::get(name.clone()).is_none());
+		assert_eq!(
+			murmur::PendingGuardians::<Test>::get(name.clone()),
+			Some((11, (guardian, ProxyType::JustTransfer, delay))),
+		);
+		System::assert_has_event(
+			murmur::Event::GuardianScheduled {
+				name: name.clone(),
+				guardian,
+				proxy_type: ProxyType::JustTransfer,
+				delay,
+				at: 11,
+			}.into()
+		);
+		System::set_block_number(10);
+		assert_noop!(
+			Murmur::apply_guardian(RuntimeOrigin::signed(guardian), name.clone()),
+			Error::<Test>::NoDueGuardian,
+		);
+		System::set_block_number(11);
+		assert_ok!(Murmur::apply_guardian(RuntimeOrigin::signed(guardian), name.clone()));
+
+		assert!(murmur::PendingGuardians::<Test>::get(name.clone()).is_none());
+		assert_eq!(
+			murmur::Guardians::<Test>::get(name.clone()),
+			Some((guardian, ProxyType::JustTransfer, delay)),
+		);
+		let def = pallet_proxy::Pallet::<Test>::find_proxy(&address, Some(guardian), None).unwrap();
+		assert_eq!((def.proxy_type, def.delay), (ProxyType::JustTransfer, delay));
+		System::assert_has_event(
+			murmur::Event::GuardianSet {
+				name,
+				address,
+				guardian: Some(guardian),
+				proxy_type: ProxyType::JustTransfer,
+				delay,
+			}.into()
+		);
+	});
+}
+
+#[test]
+fn a_guardian_cannot_be_allowed_more_than_the_proxy() {
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, _) = create_wallet_with(b"name", ProxyType::JustTransfer, None);
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+
+		assert_noop!(
+			Murmur::do_set_guardian(&name, &address, Some(7), ProxyType::Any, 10),
+			Error::<Test>::InvalidGuardianType,
+		);
+		// the owner must have time to reject the calls of the guardian
+		assert_noop!(
+			Murmur::do_set_guardian(&name, &address, Some(7), ProxyType::JustTransfer, 4),
+			Error::<Test>::GuardianDelayTooShort,
+		);
+		assert_ok!(Murmur::do_set_guardian(&name, &address, Some(7), ProxyType::JustTransfer, 5));
+
+		// the guardian is removed with the proxy type it was added with
+		assert_ok!(Murmur::do_set_guardian(&name, &address, None, ProxyType::Any, 0));
+		assert!(murmur::Guardians::<Test>::get(name).is_none());
+		assert!(pallet_proxy::Proxies::<Test>::get(address).0.is_empty());
+	});
+}
+
+#[test]
+fn a_transferred_proxy_loses_its_guardian() {
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, mmr_store) = create_wallet(b"name");
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+		assert_ok!(Murmur::do_set_guardian(&name, &address, Some(7), ProxyType::Any, 10));
+		murmur::PendingGuardians::<Test>::insert(&name, (11, (8, ProxyType::Any, 10)));
+
+		let new_root = vec![1; 32];
+		let payload = (crate::TRANSFER_TAG, Some(1u64), new_root.clone(), 1u64).encode();
+		let (position, hash, ciphertext, proof) = otp_for(&mmr_store, payload);
+		assert_ok!(Murmur::transfer_name(
+			RuntimeOrigin::signed(1),
+			name.clone(),
+			position,
+			hash,
+			ciphertext,
+			proof,
+			new_root.clone(),
+			1,
+		));
+
+		let details = murmur::Registry::<Test>::get(name.clone()).unwrap();
+		assert_eq!((details.address, details.root.to_vec(), details.size), (address, new_root, 1));
+		assert!(murmur::Guardians::<Test>::get(name.clone()).is_none());
+		assert!(murmur::PendingGuardians::<Test>::get(name.clone()).is_none());
+		assert!(pallet_proxy::Pallet::<Test>::find_proxy(&address, Some(7), None).is_err());
+		System::assert_has_event(murmur::Event::OtpProxyTransferred { name, address }.into());
+	});
+}
+
+#[test]
+fn removing_a_guardian_applies_at_once_and_cancels_a_pending_one() {
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, mmr_store) = create_wallet(b"name");
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+		assert_ok!(Murmur::do_set_guardian(&name, &address, Some(7), ProxyType::Any, 10));
+		// a guardian set with a leaked OTP
+		murmur::PendingGuardians::<Test>::insert(&name, (11, (8, ProxyType::Any, 10)));
+
+		let payload = (crate::GUARDIAN_TAG, Some(1u64), None::<u64>, ProxyType::Any, 0u64).encode();
+		let (position, hash, ciphertext, proof) = otp_for(&mmr_store, payload);
+		assert_ok!(Murmur::set_guardian(
+			RuntimeOrigin::signed(1),
+			name.clone(),
+			position,
+			hash,
+			ciphertext,
+			proof,
+			None,
+			ProxyType::Any,
+			0,
+		));
+
+		assert!(murmur::Guardians::<Test>::get(name.clone()).is_none());
+		assert!(murmur::PendingGuardians::<Test>::get(name.clone()).is_none());
+		assert!(pallet_proxy::Proxies::<Test>::get(address).0.is_empty());
+		System::set_block_number(11);
+		assert_noop!(
+			Murmur::apply_guardian(RuntimeOrigin::signed(8), name),
+			Error::<Test>::NoDueGuardian,
+		);
+	});
+}
+
+#[test]
+fn a_proxy_can_only_be_transferred_within_its_spending_limit() {
+	let limit = murmur::SpendingLimit { amount: 1_000, period: 10 };
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, mmr_store) = create_wallet_with(b"name", ProxyType::Any, Some(limit));
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+
+		let new_root = vec![1; 32];
+		let transfer = || {
+			let payload = (crate::TRANSFER_TAG, Some(1u64), new_root.clone(), 1u64).encode();
+			let (position, hash, ciphertext, proof) = otp_for(&mmr_store, payload);
+			Murmur::transfer_name(
+				RuntimeOrigin::signed(1),
+				name.clone(),
+				position,
+				hash,
+				ciphertext,
+				proof,
+				new_root.clone(),
+				1,
+			)
+		};
+
+		// a leaked OTP cannot hand over more than the proxy can spend
+		let e = transfer().unwrap_err();
+		assert_eq!(e.error, Error::<Test>::SpendingLimitExceeded.into());
+		let size = murmur::Registry::<Test>::get(name.clone()).unwrap().size;
+		assert_eq!(size, leaf_index_to_mmr_size(2));
+
+		assert_ok!(Balances::transfer_allow_death(
+			RuntimeOrigin::signed(address),
+			0,
+			Balances::free_balance(address) - 1_000,
+		));
+		assert_ok!(transfer());
+		assert_eq!(murmur::Registry::<Test>::get(name).unwrap().root.to_vec(), new_root);
+	});
+}
+
+#[test]
+fn names_are_normalized_and_charged_by_length() {
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let reserved = Balances::reserved_balance(0);
+		let (name, _) = create_wallet(b"My-Wallet_1");
+		assert_eq!(name.to_vec(), b"My-Wallet_1".to_vec());
+
+		// the wallet is registered under its lowercase name, for a deposit of 10 + 2 per byte
+		let normalized = Murmur::normalize(b"my-wallet_1").unwrap();
+		assert!(murmur::Registry::<Test>::get(&normalized).is_some());
+		let spawn = murmur::Spawns::<Test>::get(&normalized).unwrap();
+		assert_eq!(spawn.deposit, 10 + 2 * 11);
+		assert!(Balances::reserved_balance(0) >= reserved + spawn.deposit);
+
+		// names that differ only in case collide
+		assert_noop!(
+			Murmur::create(
+				RuntimeOrigin::signed(0),
+				vec![0; 32],
+				1,
+				BoundedVec::truncate_from(b"MY-WALLET_1".to_vec()),
+				ProxyType::Any,
+				None,
+			),
+			Error::<Test>::DuplicateName,
+		);
+		for invalid in [&b""[..], b"my wallet", b"wallet!", "wälle".as_bytes()] {
+			assert_noop!(
+				Murmur::create(
+					RuntimeOrigin::signed(0),
+					vec![0; 32],
+					1,
+					BoundedVec::truncate_from(invalid.to_vec()),
+					ProxyType::Any,
+					None,
+				),
+				Error::<Test>::InvalidName,
+			);
+		}
+		assert_noop!(
+			Murmur::create(
+				RuntimeOrigin::signed(0),
+				vec![0; 33],
+				1,
+				BoundedVec::truncate_from(b"other".to_vec()),
+				ProxyType::Any,
+				None,
+			),
+			Error::<Test>::InvalidRoot,
+		);
+	});
+}
+
+#[test]
+fn reserved_names_cannot_be_registered() {
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let name: murmur::Name = BoundedVec::truncate_from(b"Treasury".to_vec());
+		assert_noop!(
+			Murmur::set_name_reserved(RuntimeOrigin::signed(0), name.clone(), true),
+			BadOrigin,
+		);
+		assert_ok!(Murmur::set_name_reserved(RuntimeOrigin::root(), name.clone(), true));
+		let treasury = Murmur::normalize(&name).unwrap();
+		System::assert_last_event(
+			murmur::Event::NameReservationSet { name: treasury.clone(), reserved: true }.into()
+		);
+
+		assert_noop!(
+			Murmur::create(RuntimeOrigin::signed(0), vec![0; 32], 1, treasury.clone(), ProxyType::Any, None),
+			Error::<Test>::ReservedName,
+		);
+
+		assert_ok!(Murmur::set_name_reserved(RuntimeOrigin::root(), name, false));
+		assert_ok!(Murmur::create(RuntimeOrigin::signed(0), vec![0; 32], 1, treasury, ProxyType::Any, None));
+	});
+}
+
+#[test]
+fn wallets_can_be_looked_up_by_name_and_address() {
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, mmr_store) = create_wallet(b"Name");
+		let details = Murmur::proxy_details(&name).unwrap();
+		assert_eq!(details.root.to_vec(), mmr_store.root.0.to_vec());
+		assert_eq!(details.size, leaf_index_to_mmr_size(2));
+		assert_eq!(Murmur::proxy_details(b"name"), Some(details.clone()));
+		assert_eq!(Murmur::name_of(&details.address), Some(Murmur::normalize(b"name").unwrap()));
+
+		assert_eq!(Murmur::proxy_details(b"other"), None);
+		assert_eq!(Murmur::proxy_details(b"not a name"), None);
+		assert_eq!(Murmur::name_of(&0), None);
+		assert_eq!(Murmur::latest(), 1);
+	});
+}
+
+fn call_transfer(value: u128) -> RuntimeCall {
+	RuntimeCall::Balances(pallet_balances::Call::transfer_allow_death { dest: 5, value })
+}
+
+#[test]
+fn a_proxy_can_only_dispatch_calls_allowed_by_its_type() {
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, mmr_store) = create_wallet_with(b"name", ProxyType::JustTransfer, None);
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+		assert_eq!(murmur::Spawns::<Test>::get(&name).unwrap().proxy_type, ProxyType::JustTransfer);
+
+		let call = call_remark(vec![1, 2, 3, 4, 5]);
+		let (position, hash, ciphertext, proof) =
+			otp_for(&mmr_store, (crate::PROXY_TAG, Some(1u64), &call).encode());
+		assert_ok!(Murmur::proxy(
+			RuntimeOrigin::signed(1),
+			name.clone(),
+			position,
+			hash,
+			ciphertext,
+			proof,
+			Box::new(call),
+			false,
+		));
+		System::assert_has_event(
+			pallet_proxy::Event::ProxyExecuted {
+				result: Err(frame_system::Error::<Test>::CallFiltered.into()),
+			}
+			.into()
+		);
+	});
+}
+
+#[test]
+fn the_result_of_a_proxied_call_is_reported() {
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, mmr_store) = create_wallet(b"name");
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+
+		// the proxy cannot afford the transfer
+		let call = call_transfer(20_000);
+		let (position, hash, ciphertext, proof) =
+			otp_for(&mmr_store, (crate::PROXY_TAG, Some(1u64), &call).encode());
+		let proxy = |fail_on_error: bool| Murmur::proxy(
+			RuntimeOrigin::signed(1),
+			name.clone(),
+			position,
+			hash.clone(),
+			ciphertext.clone(),
+			proof.clone(),
+			Box::new(call.clone()),
+			fail_on_error,
+		);
+		let inner_error: sp_runtime::DispatchError =
+			sp_runtime::TokenError::FundsUnavailable.into();
+
+		// only a relayer can ask for the call to fail, since it then pays for it
+		let unsigned = murmur::Call::<Test>::proxy {
+			name: name.clone(),
+			position,
+			hash: hash.clone(),
+			ciphertext: ciphertext.clone(),
+			proof: proof.clone(),
+			call: Box::new(call.clone()),
+			fail_on_error: true,
+		};
+		assert_eq!(
+			<Murmur as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &unsigned),
+			InvalidTransaction::Call.into(),
+		);
+
+		// failing reverts the use of the OTP and the repayment of the relayer
+		let e = proxy(true).unwrap_err();
+		assert_eq!(e.error, inner_error);
+		assert!(e.post_info.actual_weight.is_some());
+		assert_eq!(murmur::LastExecuted::<Test>::get(&name), None);
+		assert_eq!(Balances::free_balance(1), 0);
+
+		assert_ok!(proxy(false));
+		assert_eq!(Balances::free_balance(1), 500);
+		System::assert_has_event(
+			murmur::Event::OtpProxyExecuted { name, position, result: Err(inner_error) }.into()
+		);
+	});
+}
+
+#[test]
+fn a_proxy_cannot_spend_more_than_its_limit() {
+	let limit = murmur::SpendingLimit { amount: 100, period: 10 };
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			Murmur::create(
+				RuntimeOrigin::signed(0),
+				vec![0; 32],
+				1,
+				BoundedVec::truncate_from(b"name".to_vec()),
+				ProxyType::Any,
+				Some(murmur::SpendingLimit { amount: 100, period: 0 }),
+			),
+			Error::<Test>::InvalidSpendingLimit,
+		);
+
+		let (name, mmr_store) = create_wallet_with(b"name", ProxyType::Any, Some(limit.clone()));
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+		assert_eq!(murmur::SpendingLimits::<Test>::get(&name), Some(limit));
+
+		let proxy = |call: RuntimeCall| {
+			let (position, hash, ciphertext, proof) =
+				otp_for(&mmr_store, (crate::PROXY_TAG, Some(1u64), &call).encode());
+			Murmur::proxy(
+				RuntimeOrigin::signed(1),
+				name.clone(),
+				position,
+				hash,
+				ciphertext,
+				proof,
+				Box::new(call),
+				false,
+			)
+		};
+
+		// the limit is checked before the call is dispatched, or an unsigned call is accepted
+		let (position, hash, ciphertext, proof) =
+			otp_for(&mmr_store, (crate::PROXY_TAG, None::<u64>, call_transfer(150)).encode());
+		let unsigned = murmur::Call::<Test>::proxy {
+			name: name.clone(),
+			position,
+			hash,
+			ciphertext,
+			proof,
+			call: Box::new(call_transfer(150)),
+			fail_on_error: false,
+		};
+		assert_eq!(
+			<Murmur as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &unsigned),
+			InvalidTransaction::Call.into(),
+		);
+		let e = proxy(call_transfer(150)).unwrap_err();
+		assert_eq!(e.error, Error::<Test>::SpendingLimitExceeded.into());
+		assert_eq!(Balances::free_balance(5), 0);
+		assert_eq!(murmur::LastExecuted::<Test>::get(&name), None);
+
+		assert_ok!(proxy(call_transfer(60)));
+		assert_eq!(Balances::free_balance(5), 60);
+		assert_eq!(murmur::Spent::<Test>::get(&name), Some((0, 60)));
+
+		// the spending is reset in the next period
+		assert!(Murmur::check_spend(&name, 50).is_err());
+		System::set_block_number(10);
+		assert_eq!(Murmur::check_spend(&name, 50).ok(), Some(Some((10, 50))));
+	});
+}
+
+#[test]
+fn a_failed_call_does_not_count_against_the_spending_limit() {
+	let limit = murmur::SpendingLimit { amount: 100, period: 10 };
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let (name, mmr_store) = create_wallet_with(b"name", ProxyType::Any, Some(limit));
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		// the proxy cannot afford the transfer once the relayer is repaid
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 520));
+
+		let call = call_transfer(60);
+		let (position, hash, ciphertext, proof) =
+			otp_for(&mmr_store, (crate::PROXY_TAG, Some(1u64), &call).encode());
+		assert_ok!(Murmur::proxy(
+			RuntimeOrigin::signed(1),
+			name.clone(),
+			position,
+			hash,
+			ciphertext,
+			proof,
+			Box::new(call),
+			false,
+		));
+
+		assert_eq!(Balances::free_balance(5), 0);
+		assert_eq!(murmur::Spent::<Test>::get(&name), None);
+	});
+}
+
+#[test]
+fn a_lower_spending_limit_applies_at_once() {
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let limit = murmur::SpendingLimit { amount: 100, period: 10 };
+		let (name, mmr_store) = create_wallet_with(b"name", ProxyType::Any, Some(limit));
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+		murmur::PendingSpendingLimits::<Test>::insert(&name, (11, None));
+
+		let lower = Some(murmur::SpendingLimit { amount: 50, period: 20 });
+		let (position, hash, ciphertext, proof) =
+			otp_for(&mmr_store, (crate::LIMIT_TAG, Some(1u64), lower.clone()).encode());
+		assert_ok!(Murmur::set_spending_limit(
+			RuntimeOrigin::signed(1),
+			name.clone(),
+			position,
+			hash,
+			ciphertext,
+			proof,
+			lower.clone(),
+		));
+
+		// the pending removal is canceled
+		assert_eq!(murmur::SpendingLimits::<Test>::get(&name), lower);
+		assert!(murmur::PendingSpendingLimits::<Test>::get(&name).is_none());
+		System::assert_has_event(murmur::Event::SpendingLimitSet { name, limit: lower }.into());
+	});
+}
+
+#[test]
+fn removing_the_spending_limit_is_delayed() {
+	new_test_ext(vec![0]).execute_with(|| {
+		System::set_block_number(1);
+		let limit = murmur::SpendingLimit { amount: 100, period: 10 };
+		let (name, mmr_store) = create_wallet_with(b"name", ProxyType::Any, Some(limit.clone()));
+		let address = murmur::Registry::<Test>::get(name.clone()).unwrap().address;
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+		murmur::Spent::<Test>::insert(&name, (0, 60));
+
+		let none: Option<murmur::SpendingLimitOf<Test>> = None;
+		let (position, hash, ciphertext, proof) =
+			otp_for(&mmr_store, (crate::LIMIT_TAG, Some(1u64), none.clone()).encode());
+		assert_ok!(Murmur::set_spending_limit(
+			RuntimeOrigin::signed(1),
+			name.clone(),
+			position,
+			hash,
+			ciphertext,
+			proof,
+			none,
+		));
+
+		// a leaked OTP cannot lift the limit before the owner can override it
+		assert_eq!(murmur::SpendingLimits::<Test>::get(&name), Some(limit));
+		assert_eq!(murmur::PendingSpendingLimits::<Test>::get(&name), Some((11, None)));
+		System::assert_has_event(
+			murmur::Event::SpendingLimitScheduled { name: name.clone(), limit: None, at: 11 }.into()
+		);
+		assert!(Murmur::check_spend(&name, 50).is_err());
+
+		System::set_block_number(11);
+		assert_eq!(Murmur::check_spend(&name, 1_000).ok(), Some(None));
+		Murmur::apply_pending_limit(&name);
+		assert_eq!(murmur::SpendingLimits::<Test>::get(&name), None);
+		assert_eq!(murmur::PendingSpendingLimits::<Test>::get(&name), None);
+		assert_eq!(murmur::Spent::<Test>::get(&name), None);
+		System::assert_has_event(murmur::Event::SpendingLimitSet { name, limit: None }.into());
+	});
+}
+
+#[test]
+fn migrate_to_v1_moves_proxies_to_their_normalized_names_and_indexes_them() {
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+	use murmur::migrations::{v0, v1};
+
+	frame_support::parameter_types! {
+		pub OldNames: Vec<Vec<u8>> = vec![
+			b"Alice".to_vec(),
+			b"bob".to_vec(),
+			b"Bob".to_vec(),
+			b"b@d".to_vec(),
+			b"long".to_vec(),
+		];
+	}
+
+	new_test_ext(vec![0]).execute_with(|| {
+		StorageVersion::new(0).put::<Murmur>();
+		for (i, raw) in OldNames::get().into_iter().enumerate() {
+			let root = if raw == b"long" { vec![2; 33] } else { vec![1; 32] };
+			let details = v0::MurmurProxyDetails { address: 10 + i as u64, root, size: 1 };
+			v0::Registry::<Test>::insert(raw, details);
+		}
+
+		#[cfg(feature = "try-runtime")]
+		let state = {
+			let state = v1::MigrateToV1::<Test, OldNames>::pre_upgrade().unwrap();
+			// the dry run of the migration is reverted
+			assert!(v0::Registry::<Test>::get(b"Alice".to_vec()).is_some());
+			assert!(Murmur::name_of(&10).is_none());
+			state
+		};
+		v1::MigrateToV1::<Test, OldNames>::on_runtime_upgrade();
+		// the proxies that were left in place are not reported as lost
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(v1::MigrateToV1::<Test, OldNames>::post_upgrade(state));
+		assert_eq!(Murmur::on_chain_storage_version(), StorageVersion::new(1));
+
+		let alice = Murmur::proxy_details(b"alice").unwrap();
+		assert_eq!((alice.address, alice.root.to_vec(), alice.size), (10, vec![1; 32], 1));
+		assert!(v0::Registry::<Test>::get(b"Alice".to_vec()).is_none());
+		assert_eq!(Murmur::proxy_details(b"BOB").map(|d| d.address), Some(11));
+		assert_eq!(Murmur::name_of(&10).map(|n| n.to_vec()), Some(b"alice".to_vec()));
+		assert_eq!(Murmur::name_of(&11).map(|n| n.to_vec()), Some(b"bob".to_vec()));
+
+		// a proxy whose name is taken, invalid, or whose root is too long is left in place
+		assert_eq!(v0::Registry::<Test>::get(b"Bob".to_vec()).map(|d| d.address), Some(12));
+		assert_eq!(v0::Registry::<Test>::get(b"b@d".to_vec()).map(|d| d.address), Some(13));
+		assert_eq!(v0::Registry::<Test>::get(b"long".to_vec()).map(|d| d.address), Some(14));
+		assert!(Murmur::proxy_details(b"long").is_none());
+		assert!((12..15).all(|address| Murmur::name_of(&address).is_none()));
+
+		// a second run is skipped, even once a name is released
+		murmur::Registry::<Test>::remove(murmur::Name::truncate_from(b"bob".to_vec()));
+		v1::MigrateToV1::<Test, OldNames>::on_runtime_upgrade();
+		assert!(Murmur::proxy_details(b"bob").is_none());
+	});
+}
+
+#[test]
+fn a_proxy_created_before_v1_can_proxy_calls_after_the_migration() {
+	use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+	use murmur::migrations::{v0, v1};
+
+	frame_support::parameter_types! {
+		pub OldNames: Vec<Vec<u8>> = vec![b"Name".to_vec()];
+	}
+
+	let seed = b"seed".to_vec();
+	let name = BoundedVec::<u8, ConstU32<32>>::truncate_from(b"name".to_vec());
+	let when = 1;
+	let ephem_msk = [3; 32];
+	let size = leaf_index_to_mmr_size(2);
+
+	new_test_ext(vec![0]).execute_with(|| {
+		let round_pubkey_bytes = Etf::round_pubkey().to_vec();
+		let round_pubkey = DoublePublicKey::<TinyBLS377>::from_bytes(&round_pubkey_bytes).unwrap();
+		let mmr_store = MurmurStore::new::<TinyBLS377, BasicIdBuilder>(
+			seed.clone().into(),
+			vec![1, 2, 3],
+			ephem_msk,
+			round_pubkey,
+		);
+
+		// before v1, the pure proxy was spawned with the default proxy type and only the name
+		// as it was given was recorded
+		assert_ok!(Murmur::create(
+			RuntimeOrigin::signed(0),
+			mmr_store.root.0.to_vec(),
+			size,
+			name.clone(),
+			ProxyType::default(),
+			None,
+		));
+		let address = murmur::Registry::<Test>::take(&name).unwrap().address;
+		murmur::Spawns::<Test>::remove(&name);
+		murmur::Names::<Test>::remove(&address);
+		v0::Registry::<Test>::insert(
+			b"Name".to_vec(),
+			v0::MurmurProxyDetails { address, root: mmr_store.root.0.to_vec(), size },
+		);
+		StorageVersion::new(0).put::<Murmur>();
+		v1::MigrateToV1::<Test, OldNames>::on_runtime_upgrade();
+		assert!(murmur::Spawns::<Test>::get(&name).is_none());
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(0), address, 10_000));
+
+		let call = call_remark(vec![1, 2, 3, 4, 5]);
+		let (proof, commitment, ciphertext, pos) = mmr_store.execute(
+			seed.clone(),
+			when as u32,
+			(crate::PROXY_TAG, Some(0u64), &call).encode(),
+		).unwrap();
+		let proof_items: Vec<Vec<u8>> = proof.proof_items().iter()
+			.map(|leaf| leaf.0.to_vec())
+			.collect::<Vec<_>>();
+
+		assert_ok!(Murmur::proxy(
+			RuntimeOrigin::signed(0),
+			name.clone(),
+			pos,
+			commitment,
+			ciphertext,
+			proof_items,
+			Box::new(call),
+			false,
 		));
+		assert_eq!(murmur::LastExecuted::<Test>::get(&name), Some(when));
 
+		// a guardian is checked against the default proxy type
+		assert_ok!(Murmur::do_set_guardian(&name, &address, Some(5), ProxyType::JustTransfer, 10));
 	});
 }
 
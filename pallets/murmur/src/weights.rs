@@ -1,55 +1,230 @@
+//! Weights for pallet_murmur
+//!
+//! These weights are estimates and have not been measured yet. They must be replaced by the
+//! output of the benchmarks in `benchmarking.rs`, generated with:
+//!
+//! ./target/release/node benchmark pallet --chain dev --wasm-execution=compiled
+//!     --pallet pallet_murmur --extrinsic '*' --steps 50 --repeat 20
+//!     --output pallets/murmur/src/weights.rs
+//!
+//! The weight of each call authorized by an OTP includes the verification of the OTP, which is
+//! dominated by its timelock decryption and so by a pairing. The component `p` is the number of
+//! items in the merkle proof of the OTP.
+//!
+//! Longer hashes, ciphertexts and proof items are rejected before they are hashed, but the cost
+//! of an OTP whose ciphertext is `MaxCiphertextLen` bytes long has not been measured either, so
+//! `MaxCiphertextLen` should not be set above the length of the ciphertexts of the murmur client.
 
-// //! Autogenerated weights for `pallet_etf`
-// //!
-// //! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-// //! DATE: 2023-07-28, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
-// //! WORST CASE MAP SIZE: `1000000`
-// //! HOSTNAME: `DESKTOP-RN9BJOQ`, CPU: `Intel(R) Core(TM) i7-9700KF CPU @ 3.60GHz`
-// //! EXECUTION: None, WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
 
-// // Executed Command:
-// // ./target/release/node-template
-// // benchmark
-// // pallet
-// // --chain
-// // dev
-// // --wasm-execution=compiled
-// // --pallet
-// // pallet_etf
-// // --extrinsic
-// // *
-// // --steps
-// // 50
-// // --repeat
-// // 20
-// // --output
-// // bin/node-template/pallets/etf/src/weight.rs
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
 
-// #![cfg_attr(rustfmt, rustfmt_skip)]
-// #![allow(unused_parens)]
-// #![allow(unused_imports)]
-// #![allow(missing_docs)]
+/// Weight functions needed for pallet_murmur.
+pub trait WeightInfo {
+	fn create() -> Weight;
+	fn proxy(p: u32, ) -> Weight;
+	fn update_root(p: u32, l: u32, ) -> Weight;
+	fn transfer_name(p: u32, ) -> Weight;
+	fn close(p: u32, ) -> Weight;
+	fn set_guardian(p: u32, ) -> Weight;
+	fn set_name_reserved() -> Weight;
+	fn set_spending_limit(p: u32, ) -> Weight;
+	fn apply_guardian() -> Weight;
+}
 
-// use frame_support::{traits::Get, weights::Weight};
-// use core::marker::PhantomData;
-// use crate as pallet_etf;
+/// Weights for pallet_murmur using the Substrate node and recommended hardware.
+pub struct SubstrateWeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeightInfo<T> {
+	/// Storage: `Murmur::Registry` (r:1 w:1)
+	/// Storage: `Murmur::ReservedNames` (r:1 w:0)
+	/// Storage: `Murmur::Spawns` (r:0 w:1)
+	/// Storage: `Murmur::Names` (r:0 w:1)
+	/// Storage: `Murmur::SpendingLimits` (r:0 w:1)
+	/// Storage: `Proxy::Proxies` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn create() -> Weight {
+		Weight::from_parts(70_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	/// Storage: `Murmur::Registry` (r:1 w:0)
+	/// Storage: `Murmur::Spawns` (r:1 w:0)
+	/// Storage: `Murmur::SpendingLimits` (r:1 w:1)
+	/// Storage: `Murmur::PendingSpendingLimits` (r:1 w:1)
+	/// Storage: `Murmur::Spent` (r:1 w:1)
+	/// Storage: `Proxy::Proxies` (r:1 w:0)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `Murmur::LastExecuted` (r:1 w:1)
+	/// Storage: `RandomnessBeacon::Height` (r:1 w:0)
+	/// Storage: `RandomnessBeacon::Pulses` (r:1 w:0)
+	/// Storage: `Etf::RoundKeys` (r:1 w:0)
+	/// The range of component `p` is `[1, 8]`.
+	fn proxy(p: u32, ) -> Weight {
+		Weight::from_parts(485_000_000, 0)
+			.saturating_add(Weight::from_parts(900_000, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	/// Storage: `Murmur::Registry` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `Murmur::LastExecuted` (r:1 w:1)
+	/// Storage: `RandomnessBeacon::Height` (r:1 w:0)
+	/// Storage: `RandomnessBeacon::Pulses` (r:1 w:0)
+	/// Storage: `Etf::RoundKeys` (r:1 w:0)
+	/// The range of component `p` is `[1, 8]`.
+	/// The range of component `l` is `[1, 1024]`.
+	fn update_root(p: u32, l: u32, ) -> Weight {
+		Weight::from_parts(475_000_000, 0)
+			.saturating_add(Weight::from_parts(900_000, 0).saturating_mul(p.into()))
+			.saturating_add(Weight::from_parts(1_800_000, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Storage: `Murmur::Registry` (r:1 w:1)
+	/// Storage: `Murmur::SpendingLimits` (r:1 w:0)
+	/// Storage: `Murmur::PendingSpendingLimits` (r:1 w:0)
+	/// Storage: `Murmur::Spent` (r:1 w:0)
+	/// Storage: `Murmur::PendingGuardians` (r:0 w:1)
+	/// Storage: `Murmur::Guardians` (r:1 w:1)
+	/// Storage: `Proxy::Proxies` (r:1 w:1)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Storage: `Murmur::LastExecuted` (r:1 w:1)
+	/// Storage: `RandomnessBeacon::Height` (r:1 w:0)
+	/// Storage: `RandomnessBeacon::Pulses` (r:1 w:0)
+	/// Storage: `Etf::RoundKeys` (r:1 w:0)
+	/// The range of component `p` is `[1, 8]`.
+	fn transfer_name(p: u32, ) -> Weight {
+		Weight::from_parts(505_000_000, 0)
+			.saturating_add(Weight::from_parts(900_000, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(12))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	/// Storage: `Murmur::Registry` (r:1 w:1)
+	/// Storage: `Murmur::Spawns` (r:1 w:1)
+	/// Storage: `Murmur::Guardians` (r:1 w:1)
+	/// Storage: `Murmur::PendingGuardians` (r:0 w:1)
+	/// Storage: `Murmur::Names` (r:0 w:1)
+	/// Storage: `Murmur::SpendingLimits` (r:1 w:1)
+	/// Storage: `Murmur::PendingSpendingLimits` (r:1 w:1)
+	/// Storage: `Murmur::Spent` (r:1 w:1)
+	/// Storage: `Proxy::Proxies` (r:1 w:1)
+	/// Storage: `System::Account` (r:3 w:3)
+	/// Storage: `Murmur::LastExecuted` (r:1 w:1)
+	/// Storage: `RandomnessBeacon::Height` (r:1 w:0)
+	/// Storage: `RandomnessBeacon::Pulses` (r:1 w:0)
+	/// Storage: `Etf::RoundKeys` (r:1 w:0)
+	/// The range of component `p` is `[1, 8]`.
+	fn close(p: u32, ) -> Weight {
+		Weight::from_parts(540_000_000, 0)
+			.saturating_add(Weight::from_parts(900_000, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(14))
+			.saturating_add(T::DbWeight::get().writes(14))
+	}
+	/// Storage: `Murmur::Registry` (r:1 w:0)
+	/// Storage: `Murmur::Spawns` (r:1 w:0)
+	/// Storage: `Murmur::PendingGuardians` (r:0 w:1)
+	/// Storage: `Murmur::Guardians` (r:1 w:1)
+	/// Storage: `Proxy::Proxies` (r:1 w:1)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Storage: `Murmur::LastExecuted` (r:1 w:1)
+	/// Storage: `RandomnessBeacon::Height` (r:1 w:0)
+	/// Storage: `RandomnessBeacon::Pulses` (r:1 w:0)
+	/// Storage: `Etf::RoundKeys` (r:1 w:0)
+	/// The range of component `p` is `[1, 8]`.
+	fn set_guardian(p: u32, ) -> Weight {
+		Weight::from_parts(505_000_000, 0)
+			.saturating_add(Weight::from_parts(900_000, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	/// Storage: `Murmur::ReservedNames` (r:0 w:1)
+	fn set_name_reserved() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Murmur::Registry` (r:1 w:0)
+	/// Storage: `Murmur::SpendingLimits` (r:1 w:1)
+	/// Storage: `Murmur::PendingSpendingLimits` (r:1 w:1)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Storage: `Murmur::LastExecuted` (r:1 w:1)
+	/// Storage: `RandomnessBeacon::Height` (r:1 w:0)
+	/// Storage: `RandomnessBeacon::Pulses` (r:1 w:0)
+	/// Storage: `Etf::RoundKeys` (r:1 w:0)
+	/// The range of component `p` is `[1, 8]`.
+	fn set_spending_limit(p: u32, ) -> Weight {
+		Weight::from_parts(470_000_000, 0)
+			.saturating_add(Weight::from_parts(900_000, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	/// Storage: `Murmur::Registry` (r:1 w:0)
+	/// Storage: `Murmur::PendingGuardians` (r:1 w:1)
+	/// Storage: `Murmur::Spawns` (r:1 w:0)
+	/// Storage: `Murmur::Guardians` (r:1 w:1)
+	/// Storage: `Proxy::Proxies` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn apply_guardian() -> Weight {
+		Weight::from_parts(45_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+}
 
-// pub trait WeightInfo {
-// 	fn update_ibe_params() -> Weight;
-// }
-
-// /// Weight functions for `pallet_etf`.
-// pub struct SubstrateWeightInfo<T>(PhantomData<T>);
-// impl<T: frame_system::Config> WeightInfo for SubstrateWeightInfo<T> {
-// 	/// Storage: Etf IBEParams (r:0 w:1)
-// 	/// Proof Skipped: Etf IBEParams (max_values: Some(1), max_size: None, mode: Measured)
-// 	fn update_ibe_params() -> Weight {
-// 		// Proof Size summary in bytes:
-// 		//  Measured:  `0`
-// 		//  Estimated: `0`
-// 		// Minimum execution time: 80_700_000 picoseconds.
-// 		Weight::from_parts(81_500_000, 0)
-// 			.saturating_add(Weight::from_parts(0, 0))
-// 			.saturating_add(T::DbWeight::get().writes(1))
-// 	}
-// }
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn create() -> Weight {
+		Weight::from_parts(70_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(6))
+	}
+	fn proxy(p: u32, ) -> Weight {
+		Weight::from_parts(485_000_000, 0)
+			.saturating_add(Weight::from_parts(900_000, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(11))
+			.saturating_add(RocksDbWeight::get().writes(5))
+	}
+	fn update_root(p: u32, l: u32, ) -> Weight {
+		Weight::from_parts(475_000_000, 0)
+			.saturating_add(Weight::from_parts(900_000, 0).saturating_mul(p.into()))
+			.saturating_add(Weight::from_parts(1_800_000, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn transfer_name(p: u32, ) -> Weight {
+		Weight::from_parts(505_000_000, 0)
+			.saturating_add(Weight::from_parts(900_000, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(12))
+			.saturating_add(RocksDbWeight::get().writes(7))
+	}
+	fn close(p: u32, ) -> Weight {
+		Weight::from_parts(540_000_000, 0)
+			.saturating_add(Weight::from_parts(900_000, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(14))
+			.saturating_add(RocksDbWeight::get().writes(14))
+	}
+	fn set_guardian(p: u32, ) -> Weight {
+		Weight::from_parts(505_000_000, 0)
+			.saturating_add(Weight::from_parts(900_000, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(10))
+			.saturating_add(RocksDbWeight::get().writes(6))
+	}
+	fn set_name_reserved() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn set_spending_limit(p: u32, ) -> Weight {
+		Weight::from_parts(470_000_000, 0)
+			.saturating_add(Weight::from_parts(900_000, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(9))
+			.saturating_add(RocksDbWeight::get().writes(5))
+	}
+	fn apply_guardian() -> Weight {
+		Weight::from_parts(45_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+}
@@ -35,7 +35,7 @@ pub mod weights;
 
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
-	dispatch::GetDispatchInfo,
+	dispatch::{DispatchResultWithPostInfo, GetDispatchInfo},
 	ensure,
 	traits::{Currency, Get, InstanceFilter, IsSubType, IsType, OriginTrait, ReservableCurrency},
 };
@@ -206,7 +206,7 @@ pub mod pallet {
 			let def = Self::find_proxy(&real, Some(who), force_proxy_type)?;
 			ensure!(def.delay.is_zero(), Error::<T>::Unannounced);
 
-			Self::do_proxy(def, real, *call);
+			let _ = Self::do_proxy(def, real, *call);
 
 			Ok(())
 		}
@@ -515,7 +515,7 @@ pub mod pallet {
 			})
 			.map_err(|_| Error::<T>::Unannounced)?;
 
-			Self::do_proxy(def, real, *call);
+			let _ = Self::do_proxy(def, real, *call);
 
 			Ok(())
 		}
@@ -777,11 +777,15 @@ impl<T: Config> Pallet<T> {
 		Ok(Proxies::<T>::get(real).0.into_iter().find(f).ok_or(Error::<T>::NotProxy)?)
 	}
 
+	/// Dispatch `call` from `real` through the proxy definition `def`.
+	///
+	/// The result of the call is deposited in a `ProxyExecuted` event and returned, so that
+	/// callers can account for the actual weight of the call.
 	pub fn do_proxy(
 		def: ProxyDefinition<T::AccountId, T::ProxyType, BlockNumberFor<T>>,
 		real: T::AccountId,
 		call: <T as Config>::RuntimeCall,
-	) {
+	) -> DispatchResultWithPostInfo {
 		// This is a freshly authenticated new account, the origin restrictions doesn't apply.
 		let mut origin: T::RuntimeOrigin = frame_system::RawOrigin::Signed(real).into();
 		origin.add_filter(move |c: &<T as frame_system::Config>::RuntimeCall| {
@@ -804,6 +808,7 @@ impl<T: Config> Pallet<T> {
 		});
 		let e = call.dispatch(origin);
 		Self::deposit_event(Event::ProxyExecuted { result: e.map(|_| ()).map_err(|e| e.error) });
+		e
 	}
 
 	/// Removes all proxy delegates for a given delegator.
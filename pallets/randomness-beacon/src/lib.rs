@@ -44,9 +44,12 @@ use etf_crypto_primitives::{
 };
 
 use sp_session::{GetSessionNumber, GetValidatorCount};
-use w3f_bls::{DoublePublicKey, DoubleSignature, EngineBLS, Message, SerializableToBytes, TinyBLS377};
+use w3f_bls::{
+	DoublePublicKey, DoubleSignature, EngineBLS, Message, PublicKey, SerializableToBytes, Signature,
+	TinyBLS377,
+};
 use sp_consensus_beefy_etf::{
-	Commitment, ValidatorSetId, Payload, known_payloads, BeefyAuthorityId,
+	ValidatorSetId, BeefyAuthorityId,
 };
 
 use sp_runtime::{
@@ -64,6 +67,7 @@ use log::{info, debug, error};
 mod mock;
 #[cfg(test)]
 mod tests;
+pub mod tlock;
 
 pub use pallet::*;
 
@@ -202,6 +206,10 @@ pub mod pallet {
 		AlreadyInitialized,
 		/// the bounded runtime storage has reached its limit
 		PulseOverflow,
+		/// no round public key was active at the block of the pulse
+		MissingRoundKey,
+		/// the round public key or a commitment could not be deserialized
+		InvalidRoundKey,
 	}
 
 	#[pallet::event]
@@ -225,17 +233,11 @@ pub mod pallet {
 			block_number: BlockNumberFor<T>,
 		) -> DispatchResultWithPostInfo {
 			ensure_none(origin)?;
-			let round_pk_bytes: Vec<u8> = <pallet_etf::Pallet<T>>::round_pubkey().to_vec();
-			let rk = DoublePublicKey::<TinyBLS377>::deserialize_compressed(
-				&round_pk_bytes[..]
-			).unwrap();
-			let validator_set_id = 0;//<pallet_beefy::Pallet<T>>::validator_set_id();
-			let _ = Self::try_add_pulse(
-				signatures, 
-				block_number, 
-				rk, 
-				validator_set_id
-			)?;
+			// the pulse is signed by the validators of the epoch of `block_number`,
+			// which may precede the current epoch if the pulse arrives late
+			let epoch = <pallet_etf::Pallet<T>>::epoch_at(block_number)
+				.ok_or(Error::<T>::MissingRoundKey)?;
+			Self::try_add_pulse(signatures, block_number, epoch)?;
 
 			Height::<T>::set(block_number);
 			Self::deposit_event(Event::PulseStored);
@@ -256,40 +258,44 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// add a new pulse to the hash chain
+	/// the partial signatures are checked against the commitments of `epoch`, and the
+	/// interpolated signature against its round public key, over the commitment of the
+	/// validator set that signs with that key
 	fn try_add_pulse(
 		raw_signatures: Vec<Vec<u8>>,
 		block_number: BlockNumberFor<T>,
-		rk: DoublePublicKey<TinyBLS377>,
-		validator_set_id: ValidatorSetId,
+		epoch: pallet_etf::Epoch,
 	) -> Result<(), Error<T>> {
-		let payload = Payload::from_single_entry(
-			known_payloads::ETF_SIGNATURE, 
-			Vec::new()
-		);
-		let commitment = Commitment { 
-			payload, 
-			block_number, 
-			validator_set_id,
-		};
+		let rk_bytes = <pallet_etf::Pallet<T>>::round_pubkey_at(epoch)
+			.ok_or(Error::<T>::MissingRoundKey)?;
+		let rk = DoublePublicKey::<TinyBLS377>::deserialize_compressed(&rk_bytes[..])
+			.map_err(|_| Error::<T>::InvalidRoundKey)?;
+		let commitments = <pallet_etf::Pallet<T>>::commitments_at(epoch)
+			.ok_or(Error::<T>::MissingRoundKey)?;
+		let validator_set_id = <pallet_etf::Pallet<T>>::validator_set_id_at(epoch)
+			.ok_or(Error::<T>::MissingRoundKey)?;
+		let message = Self::pulse_message(block_number, validator_set_id);
 
-		// // TODO: error handling
 		let mut good_sigs = Vec::new();
-		raw_signatures.iter().enumerate().for_each(|(idx, rs)| {
-			let etf_pk = <pallet_etf::Pallet<T>>::commitments()[idx].encode();
-			let pk = DoublePublicKey::<TinyBLS377>::deserialize_compressed(
-				&etf_pk[..]
-			).unwrap();
+		for (idx, rs) in raw_signatures.iter().enumerate() {
+			let etf_pk = commitments.get(idx).ok_or(Error::<T>::InvalidSignature)?.encode();
+			let pk = DoublePublicKey::<TinyBLS377>::deserialize_compressed(&etf_pk[..])
+				.map_err(|_| Error::<T>::InvalidRoundKey)?;
 
 			if let Ok(sig) = DoubleSignature::<TinyBLS377>::from_bytes(&rs) {	
-				if sig.verify(&Message::new(b"", &commitment.encode()), &pk) {
+				if sig.verify(&message, &pk) {
 					good_sigs.push((<TinyBLS377 as EngineBLS>::Scalar::from((idx as u8) + 1), sig.0));
 				}
 			}
-		});
+		}
+		ensure!(!good_sigs.is_empty(), Error::<T>::InvalidSignature);
 
 		let sig = interpolate_threshold_bls::<TinyBLS377>(good_sigs);
+		if !Signature::<TinyBLS377>(sig).verify(&message, &PublicKey(rk.1)) {
+			return Err(Error::<T>::InvalidSignature)
+		}
 		let mut bytes = Vec::new();
-		sig.serialize_compressed(&mut bytes).unwrap();
+		sig.serialize_compressed(&mut bytes).map_err(|_| Error::<T>::InvalidSignature)?;
 		let bounded_sig = 
 			BoundedVec::<u8, ConstU32<48>>::try_from(bytes)
 				.map_err(|_| Error::<T>::InvalidSignature)?;
@@ -304,6 +310,15 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// the message signed by the beacon to produce the pulse for `block_number`
+	fn pulse_message(
+		block_number: BlockNumberFor<T>,
+		validator_set_id: ValidatorSetId,
+	) -> Message {
+		let commitment = tlock::commitment(block_number, validator_set_id);
+		Message::new(b"", &commitment.encode())
+	}
+
 	pub fn height() -> BlockNumberFor<T> {
 		Height::<T>::get()
 	}
@@ -361,11 +376,28 @@ impl<T: Config> Pallet<T> {
 }
 
 /// errors for timelock encryption
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
 pub enum TimelockError {
+	/// the message could not be encrypted
+	EncryptionFailed,
+	/// the beacon has not (yet) produced a pulse for the target block
+	MissingPulse,
+	/// no round public key was active at the target block
+	MissingRoundKey,
+	/// the round public key could not be deserialized
+	MalformedRoundKey,
+	/// the stored pulse could not be deserialized
+	MalformedPulse,
+	/// the pulse was not produced under the round public key for the target block's epoch
+	WrongKeyEpoch,
+	/// the ciphertext could not be deserialized
+	MalformedCiphertext,
+	/// the ciphertext failed to decrypt (AEAD failure)
 	DecryptionFailed,
-	MissingSecret,
+	/// the plaintext is not a valid encoded call
+	CallDecodeFailure,
+	/// the decrypted call could not be bounded
 	BoundCallFailure,
-	DecodeFailure,
 }
 
 /// represents a timelock ciphertext
@@ -400,24 +432,31 @@ impl<T:Config> TimelockEncryptionProvider<BlockNumberFor<T>> for Pallet<T> {
 		ciphertext_bytes: &[u8], 
 		block_number: BlockNumberFor<T>
 	) -> Result<DecryptionResult, TimelockError> {
-		if let Some(secret) = Pulses::<T>::get(block_number) {
-			let pk = <pallet_etf::Pallet<T>>::round_pubkey();
-			// TODO: replace with optimized arkworks types?
-			let ciphertext:TLECiphertext<TinyBLS377> = 
-				TLECiphertext::deserialize_compressed(ciphertext_bytes)
-					.map_err(|_| TimelockError::DecodeFailure)?;
-
-			let sig: <TinyBLS377 as EngineBLS>::SignatureGroup = 
-				<TinyBLS377 as EngineBLS>::SignatureGroup::deserialize_compressed(
-					&secret.body.signature.to_vec()[..]
-				).map_err(|_| TimelockError::DecodeFailure)?;
-
-			let plaintext = ciphertext.tld(sig)
-				.map_err(|_| TimelockError::DecryptionFailed)?;
-
-			return Ok(plaintext);
+		let secret = Pulses::<T>::get(block_number).ok_or(TimelockError::MissingPulse)?;
+		// the pulse must have been produced under the round key that was active at the
+		// target block, which may differ from the current one after a rotation
+		let (epoch, pk_bytes) = <pallet_etf::Pallet<T>>::round_pubkey_at_block(block_number)
+			.ok_or(TimelockError::MissingRoundKey)?;
+		let pk = DoublePublicKey::<TinyBLS377>::deserialize_compressed(&pk_bytes[..])
+			.map_err(|_| TimelockError::MalformedRoundKey)?;
+		// TODO: replace with optimized arkworks types?
+		let ciphertext:TLECiphertext<TinyBLS377> = 
+			TLECiphertext::deserialize_compressed(ciphertext_bytes)
+				.map_err(|_| TimelockError::MalformedCiphertext)?;
+
+		let sig: <TinyBLS377 as EngineBLS>::SignatureGroup = 
+			<TinyBLS377 as EngineBLS>::SignatureGroup::deserialize_compressed(
+				&secret.body.signature.to_vec()[..]
+			).map_err(|_| TimelockError::MalformedPulse)?;
+
+		let validator_set_id = <pallet_etf::Pallet<T>>::validator_set_id_at(epoch)
+			.ok_or(TimelockError::MissingRoundKey)?;
+		let message = Self::pulse_message(block_number, validator_set_id);
+		if !Signature::<TinyBLS377>(sig).verify(&message, &PublicKey(pk.1)) {
+			return Err(TimelockError::WrongKeyEpoch)
 		}
-		Err(TimelockError::MissingSecret)
+
+		ciphertext.tld(sig).map_err(|_| TimelockError::DecryptionFailed)
 	}
 
 	fn latest() -> BlockNumberFor<T> {
@@ -425,6 +464,21 @@ impl<T:Config> TimelockEncryptionProvider<BlockNumberFor<T>> for Pallet<T> {
 	}
 }
 
+/// provides the randomness of the pulse for a given block
+pub trait BeaconRandomness<Output, BN> {
+	/// the randomness of the pulse for `block_number` hashed together with `subject`, if the
+	/// beacon has produced that pulse
+	fn random_for(subject: &[u8], block_number: BN) -> Option<Output>;
+}
+
+impl<T: Config> BeaconRandomness<T::Hash, BlockNumberFor<T>> for Pallet<T> {
+	fn random_for(subject: &[u8], block_number: BlockNumberFor<T>) -> Option<T::Hash> {
+		Pulses::<T>::get(block_number).map(|pulse| {
+			(subject, block_number, pulse.body.randomness).using_encoded(T::Hashing::hash)
+		})
+	}
+}
+
 // use frame_support::StorageHasher;
 
 
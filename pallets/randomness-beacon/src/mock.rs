@@ -96,8 +96,14 @@ impl pallet_mmr::Config for Test {
 }
 
 impl pallet_etf::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
 	type BeefyId = BeefyId;
 	type MaxAuthorities = ConstU32<100>;
+	type ShareVerifier = pallet_etf::AcssShareVerifier<w3f_bls::TinyBLS377>;
+	type UpdateOrigin = frame_system::EnsureRoot<u64>;
+	type ValidatorSet = Session;
+	type BeefyValidatorSetId = pallet_beefy::CurrentValidatorSetId<Test>;
+	type WeightInfo = ();
 }
 
 impl pallet_beefy::Config for Test {
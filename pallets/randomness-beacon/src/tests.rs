@@ -16,7 +16,7 @@
 
 use std::vec;
 use codec::Encode;
-use frame_support::{assert_ok, traits::OnInitialize};
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
 use crate::{
     self as beacon,
     BlockNumberFor,
@@ -28,7 +28,7 @@ use sha2::Sha256;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use sp_core::{bls377, Pair, ByteArray};
 use sp_consensus_beefy_etf::{
-	Commitment, ValidatorSetId, Payload, known_payloads,
+	Commitment, ValidatorSetId, Payload, known_payloads, test_utils::etf_genesis,
 };
 
 use ark_ff::Zero;
@@ -205,4 +205,139 @@ fn test_can_write_many_pulses() {
         let pulses = beacon::Pulses::<Test>::get();
         assert_eq!(pulses.len(), 2);
 	});
-}
\ No newline at end of file
+}
+
+fn write_single_validator_pulse(block_number: BlockNumberFor<Test>) {
+    let resharing_bytes_1 = &pallet_etf::Shares::<Test>::get()[0];
+    let epoch = <pallet_etf::Pallet<Test>>::epoch_at(block_number).unwrap();
+    assert_ok!(Beacon::write_pulse(
+        RuntimeOrigin::none(),
+        vec![single_validator_signature(1, resharing_bytes_1, block_number, epoch)],
+        block_number,
+    ));
+}
+
+/// the signature of validator `id` over the pulse for `block_number` in `epoch`
+fn single_validator_signature(
+    id: u8,
+    resharing: &[u8],
+    block_number: BlockNumberFor<Test>,
+    epoch: pallet_etf::Epoch,
+) -> Vec<u8> {
+    let commitment = beacon::tlock::commitment(
+        block_number,
+        <pallet_etf::Pallet<Test>>::validator_set_id_at(epoch).unwrap(),
+    );
+    let (_pk, signature) = calculate_signature(id, resharing, &commitment.encode());
+    let sig_bytes: &[u8] = signature.as_ref();
+    sig_bytes.to_vec()
+}
+
+#[test]
+fn test_encrypt_for_block_round_trips_through_decrypt_at() {
+    new_test_ext(vec![1]).execute_with(|| {
+        let round_pk_bytes: Vec<u8> = <pallet_etf::Pallet<Test>>::round_pubkey().to_vec();
+        let message = b"hello from the past".to_vec();
+        let ciphertext = beacon::tlock::encrypt_for_block(
+            &round_pk_bytes,
+            0,
+            2u64,
+            &message,
+            ark_std::test_rng(),
+        ).unwrap();
+
+        // there is no pulse for block 2 yet
+        assert!(matches!(
+            Beacon::decrypt_at(&ciphertext, 2),
+            Err(beacon::TimelockError::MissingPulse)
+        ));
+
+        write_single_validator_pulse(2);
+        init_block(2);
+
+        let result = Beacon::decrypt_at(&ciphertext, 2).ok().unwrap();
+        assert_eq!(result.message, message);
+    });
+}
+
+#[test]
+fn test_ciphertext_for_one_block_cannot_be_decrypted_at_another() {
+    new_test_ext(vec![1]).execute_with(|| {
+        let round_pk_bytes: Vec<u8> = <pallet_etf::Pallet<Test>>::round_pubkey().to_vec();
+        let ciphertext = beacon::tlock::encrypt_for_block(
+            &round_pk_bytes,
+            0,
+            3u64,
+            b"not yet",
+            ark_std::test_rng(),
+        ).unwrap();
+
+        write_single_validator_pulse(2);
+        init_block(2);
+
+        assert!(Beacon::decrypt_at(&ciphertext, 2).is_err());
+    });
+}
+
+#[test]
+fn test_encrypt_for_block_rejects_malformed_round_key() {
+    let result = beacon::tlock::encrypt_for_block(&[1u8; 10], 0, 1u64, b"msg", ark_std::test_rng());
+    assert!(matches!(result, Err(beacon::TimelockError::MalformedRoundKey)));
+}
+
+#[test]
+fn test_a_late_pulse_is_verified_against_the_key_of_its_block() {
+    new_test_ext(vec![1]).execute_with(|| {
+        init_block(1);
+        let (epoch, old_key) = <pallet_etf::Pallet<Test>>::encryption_key_for(2).unwrap();
+        assert_eq!(epoch, 0);
+        let old_share = pallet_etf::Shares::<Test>::get()[0].clone();
+        let message = b"sealed before the rotation".to_vec();
+        let ciphertext = beacon::tlock::encrypt_for_block(
+            &old_key,
+            <pallet_etf::Pallet<Test>>::validator_set_id_at(epoch).unwrap(),
+            2u64,
+            &message,
+            ark_std::test_rng(),
+        ).unwrap();
+
+        // the key rotates after block 2, before its pulse is written
+        init_block(2);
+        let (round_pubkey, resharing) = etf_genesis::<TinyBLS377>(vec![mock_beefy_id(2)]);
+        assert_ok!(Etf::stage_round(
+            RuntimeOrigin::root(),
+            round_pubkey,
+            resharing,
+            Session::current_index() + 1,
+        ));
+        init_block(3);
+        Etf::on_initialize(3);
+        assert_eq!(<pallet_etf::Pallet<Test>>::current_epoch(), 1);
+        assert_eq!(<pallet_etf::Pallet<Test>>::epoch_at(2), Some(0));
+        // the new key is used by the current BEEFY validator set, not one named after its epoch
+        assert_eq!(
+            <pallet_etf::Pallet<Test>>::validator_set_id_at(1),
+            Some(<pallet_beefy::Pallet<Test>>::validator_set_id()),
+        );
+
+        // a pulse signed by the new validators is not a pulse for block 2
+        let new_share = pallet_etf::Shares::<Test>::get()[0].clone();
+        assert_noop!(
+            Beacon::write_pulse(
+                RuntimeOrigin::none(),
+                vec![single_validator_signature(2, &new_share, 2, 1)],
+                2,
+            ),
+            Error::<Test>::InvalidSignature,
+        );
+
+        // the late pulse of the old validators is accepted and unlocks the ciphertext
+        assert_ok!(Beacon::write_pulse(
+            RuntimeOrigin::none(),
+            vec![single_validator_signature(1, &old_share, 2, 0)],
+            2,
+        ));
+        let result = Beacon::decrypt_at(&ciphertext, 2).ok().unwrap();
+        assert_eq!(result.message, message);
+    });
+}
@@ -0,0 +1,85 @@
+/*
+ * Copyright 2024 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Client-side timelock encryption for the beacon.
+//!
+//! Produces ciphertexts that [`TimelockEncryptionProvider::decrypt_at`] can decrypt once the
+//! beacon has written a pulse for the target block. This module is `no_std` compatible so it
+//! can be used both from wallets and from within the runtime (e.g. in benchmarks).
+//!
+//! [`TimelockEncryptionProvider::decrypt_at`]: crate::TimelockEncryptionProvider::decrypt_at
+
+use crate::TimelockError;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{CryptoRng, Rng};
+use codec::Encode;
+use etf_crypto_primitives::{encryption::tlock::tle, ibe::fullident::Identity};
+use sp_consensus_beefy_etf::{known_payloads, Commitment, Payload, ValidatorSetId};
+use sp_std::prelude::*;
+use w3f_bls::{DoublePublicKey, TinyBLS377};
+
+/// The commitment the beacon signs to produce the pulse for `block_number`
+pub fn commitment<BN>(block_number: BN, validator_set_id: ValidatorSetId) -> Commitment<BN> {
+	let payload = Payload::from_single_entry(known_payloads::ETF_SIGNATURE, Vec::new());
+	Commitment { payload, block_number, validator_set_id }
+}
+
+/// The identity that a ciphertext for `block_number` is encrypted to, where
+/// `validator_set_id` is the BEEFY validator set that signs with the round public key active
+/// at `block_number` (see `pallet_etf::Pallet::validator_set_id_at`).
+/// The pulse for `block_number` is the IBE secret for this identity.
+pub fn identity_for_block<BN: Encode>(block_number: BN, validator_set_id: ValidatorSetId) -> Identity {
+	Identity::new(&commitment(block_number, validator_set_id).encode())
+}
+
+/// Timelock encrypt `message` so that it can be decrypted once the beacon has produced a
+/// pulse for `block_number`
+///
+/// * `round_pubkey`: The serialized round public key (see `pallet_etf_runtime_api::EtfApi`)
+/// * `validator_set_id`: The id of the BEEFY validator set that signs with `round_pubkey`
+/// * `block_number`: The block whose pulse unlocks the ciphertext
+/// * `message`: The message to encrypt
+/// * `rng`: A source of randomness for the ephemeral secret
+///
+/// Returns the serialized ciphertext, exactly as expected by `decrypt_at`.
+pub fn encrypt_for_block<BN: Encode, R: Rng + CryptoRng + Sized>(
+	round_pubkey: &[u8],
+	validator_set_id: ValidatorSetId,
+	block_number: BN,
+	message: &[u8],
+	mut rng: R,
+) -> Result<Vec<u8>, TimelockError> {
+	let rk = DoublePublicKey::<TinyBLS377>::deserialize_compressed(round_pubkey)
+		.map_err(|_| TimelockError::MalformedRoundKey)?;
+
+	let mut ephemeral_msk = [0u8; 32];
+	rng.fill_bytes(&mut ephemeral_msk);
+
+	let ciphertext = tle::<TinyBLS377, R>(
+		rk.1,
+		ephemeral_msk,
+		message,
+		identity_for_block(block_number, validator_set_id),
+		rng,
+	)
+	.map_err(|_| TimelockError::EncryptionFailed)?;
+
+	let mut bytes = Vec::new();
+	ciphertext
+		.serialize_compressed(&mut bytes)
+		.map_err(|_| TimelockError::EncryptionFailed)?;
+	Ok(bytes)
+}
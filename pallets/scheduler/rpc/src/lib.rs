@@ -0,0 +1,145 @@
+/*
+ * Copyright 2024 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! RPC interface for the scheduler pallet.
+//!
+//! Origins are returned SCALE encoded, since the origin type of a runtime has no JSON
+//! representation.
+
+#![warn(missing_docs)]
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::{Codec, Encode};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{ErrorObject, ErrorObjectOwned},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+
+pub use pallet_scheduler_runtime_api::{
+	AgendaEntry, SchedulerApi as SchedulerRuntimeApi, TaskAddress, TaskName,
+};
+
+/// Scheduler RPC methods.
+#[rpc(client, server)]
+pub trait SchedulerApi<BlockHash, BlockNumber> {
+	/// Returns the tasks scheduled from `from` up to and including `to`.
+	#[method(name = "scheduler_agenda")]
+	fn agenda(
+		&self,
+		from: BlockNumber,
+		to: BlockNumber,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<AgendaEntry<BlockNumber, Bytes>>>;
+
+	/// Returns the earliest block whose agenda has not been fully serviced, if any.
+	#[method(name = "scheduler_incompleteSince")]
+	fn incomplete_since(&self, at: Option<BlockHash>) -> RpcResult<Option<BlockNumber>>;
+
+	/// Returns the address of the task named `id`, if it is scheduled.
+	#[method(name = "scheduler_lookup")]
+	fn lookup(
+		&self,
+		id: TaskName,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<TaskAddress<BlockNumber>>>;
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(err: impl std::fmt::Debug) -> ErrorObjectOwned {
+	ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query the scheduler.",
+		Some(format!("{:?}", err)),
+	)
+}
+
+/// Provides RPC methods to inspect the scheduler.
+pub struct Scheduler<C, Block, PalletsOrigin> {
+	client: Arc<C>,
+	_marker: PhantomData<fn() -> (Block, PalletsOrigin)>,
+}
+
+impl<C, Block, PalletsOrigin> Scheduler<C, Block, PalletsOrigin> {
+	/// Creates a new instance of the scheduler RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, BlockNumber, PalletsOrigin> SchedulerApiServer<Block::Hash, BlockNumber>
+	for Scheduler<C, Block, PalletsOrigin>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: SchedulerRuntimeApi<Block, BlockNumber, PalletsOrigin>,
+	BlockNumber: Codec + Clone + Send + Sync + 'static + serde::Serialize,
+	PalletsOrigin: Codec + 'static,
+{
+	fn agenda(
+		&self,
+		from: BlockNumber,
+		to: BlockNumber,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<AgendaEntry<BlockNumber, Bytes>>> {
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		let entries = self.client.runtime_api().agenda(at_hash, from, to).map_err(runtime_error)?;
+		Ok(entries
+			.into_iter()
+			.map(|entry| AgendaEntry {
+				address: entry.address,
+				maybe_id: entry.maybe_id,
+				priority: entry.priority,
+				origin: entry.origin.encode().into(),
+				maybe_periodic: entry.maybe_periodic,
+				sealed: entry.sealed,
+				ciphertext_len: entry.ciphertext_len,
+			})
+			.collect())
+	}
+
+	fn incomplete_since(&self, at: Option<Block::Hash>) -> RpcResult<Option<BlockNumber>> {
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client.runtime_api().incomplete_since(at_hash).map_err(runtime_error)
+	}
+
+	fn lookup(
+		&self,
+		id: TaskName,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<TaskAddress<BlockNumber>>> {
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client.runtime_api().lookup(at_hash, id).map_err(runtime_error)
+	}
+}
@@ -0,0 +1,42 @@
+/*
+ * Copyright 2024 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Runtime API definition for the scheduler pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_scheduler::{AgendaEntry, TaskAddress, TaskName};
+
+sp_api::decl_runtime_apis! {
+	/// API to inspect the tasks queued in the scheduler
+	pub trait SchedulerApi<BlockNumber, PalletsOrigin> where
+		BlockNumber: Codec,
+		PalletsOrigin: Codec,
+	{
+		/// Return the tasks scheduled from `from` up to and including `to`, including sealed
+		/// tasks whose calls are not yet known
+		fn agenda(from: BlockNumber, to: BlockNumber) -> Vec<AgendaEntry<BlockNumber, PalletsOrigin>>;
+
+		/// Return the earliest block whose agenda has not been fully serviced, if any
+		fn incomplete_since() -> Option<BlockNumber>;
+
+		/// Return the address of the task named `id`, if it is scheduled
+		fn lookup(id: TaskName) -> Option<TaskAddress<BlockNumber>>;
+	}
+}
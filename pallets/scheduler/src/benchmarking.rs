@@ -335,27 +335,142 @@ benchmarks! {
 	schedule_sealed {
 		let s in 0 .. (T::MaxScheduledPerBlock::get() - 1);
 		let when = BLOCK_NUMBER.into();
-		let periodic = Some((BlockNumberFor::<T>::one(), 100));
 		let priority = 0;
 
-		let bounded_ct: BoundedVec<u8, ConstU32<512>> = BoundedVec::new();
-		let bounded_nonce: BoundedVec<u8, ConstU32<96>> = BoundedVec::new();
-		let bounded_capsule: BoundedVec<u8, ConstU32<512>> = BoundedVec::new();
-		let origin = frame_system::RawOrigin::Signed(account("origin", 0, SEED));
+		let caller: T::AccountId = account("origin", 0, SEED);
+		let balance = T::Currency::minimum_balance()
+			.saturating_add(T::SealedDepositPerByte::get().saturating_mul(10_000u32.into()));
+		T::Currency::make_free_balance_be(&caller, balance);
+		let origin = frame_system::RawOrigin::Signed(caller.clone());
 
-		let ciphertext = Ciphertext {
-			ciphertext: bounded_ct,
-			nonce: bounded_nonce,
-			capsule: bounded_capsule,
-		};
+		let ciphertext = SealedCiphertext::Inline(vec![1u8; 4048].try_into().unwrap());
+		let max_weight = T::MaximumWeight::get();
 
 		fill_schedule_signed::<T>(when, s)?;
-	}: _(origin, when, priority, ciphertext)
+	}: _(origin, when, priority, ciphertext, max_weight)
 	verify {
 		ensure!(
 			Agenda::<T>::get(when).len() == (s + 1) as usize,
 			"didn't add to schedule"
 		);
+		ensure!(!T::Currency::reserved_balance(&caller).is_zero(), "didn't hold a deposit");
+	}
+
+	cancel_sealed {
+		let s in 1 .. T::MaxScheduledPerBlock::get();
+		let when = BLOCK_NUMBER.into();
+
+		let caller: T::AccountId = account("origin", 0, SEED);
+		let balance = T::Currency::minimum_balance()
+			.saturating_add(T::SealedDepositPerByte::get().saturating_mul(10_000u32.into()));
+		T::Currency::make_free_balance_be(&caller, balance);
+		let origin = frame_system::RawOrigin::Signed(caller.clone());
+
+		fill_schedule_signed::<T>(when, s - 1)?;
+		let ciphertext = SealedCiphertext::Inline(vec![1u8; 4048].try_into().unwrap());
+		let pallets_origin: <T as Config>::PalletsOrigin = origin.clone().into();
+		let id = ciphertext.task_name(&pallets_origin);
+		Scheduler::<T>::do_schedule_sealed(
+			DispatchTime::At(when),
+			0,
+			pallets_origin,
+			ciphertext,
+			T::MaximumWeight::get(),
+			Some(caller.clone()),
+		)?;
+	}: _(origin, id)
+	verify {
+		ensure!(Lookup::<T>::get(id).is_none(), "didn't remove from lookup");
+		ensure!(T::Currency::reserved_balance(&caller).is_zero(), "didn't release the deposit");
+	}
+
+	reschedule_sealed {
+		let s in 1 .. (T::MaxScheduledPerBlock::get() - 1);
+		let when = BLOCK_NUMBER.into();
+		let new_when = when + BlockNumberFor::<T>::one();
+
+		fill_schedule_signed::<T>(when, s - 1)?;
+		fill_schedule_signed::<T>(new_when, s - 1)?;
+		let ciphertext = SealedCiphertext::Inline(vec![1u8; 4048].try_into().unwrap());
+		let origin: <T as Config>::PalletsOrigin = frame_system::RawOrigin::Root.into();
+		let id = ciphertext.task_name(&origin);
+		Scheduler::<T>::do_schedule_sealed(
+			DispatchTime::At(when),
+			0,
+			origin,
+			ciphertext,
+			T::MaximumWeight::get(),
+			None,
+		)?;
+	}: _(frame_system::RawOrigin::Root, id, new_when)
+	verify {
+		ensure!(Lookup::<T>::get(id) == Some((new_when, s - 1)), "didn't reschedule");
+	}
+
+	schedule_named_sealed {
+		let s in 0 .. (T::MaxScheduledPerBlock::get() - 1);
+		let c in 1 .. T::MaxSealedOccurrences::get();
+		let when = BLOCK_NUMBER.into();
+		let id = u32_to_name(s);
+
+		let caller: T::AccountId = account("origin", 0, SEED);
+		let balance = T::Currency::minimum_balance().saturating_add(
+			T::SealedDepositPerByte::get().saturating_mul((4096 * c).into()),
+		);
+		T::Currency::make_free_balance_be(&caller, balance);
+		let origin = frame_system::RawOrigin::Signed(caller.clone());
+
+		let ciphertexts: BoundedVec<SealedCiphertextOf<T>, T::MaxSealedOccurrences> = (0..c)
+			.map(|i| SealedCiphertext::Inline(vec![i as u8; 4048].try_into().unwrap()))
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+		let max_weight = T::MaximumWeight::get();
+
+		fill_schedule_signed::<T>(when, s)?;
+	}: _(origin, id, when, Some(BlockNumberFor::<T>::one()), 0, ciphertexts, max_weight)
+	verify {
+		ensure!(
+			Agenda::<T>::get(when).len() == (s + 1) as usize,
+			"didn't add to schedule"
+		);
+		ensure!(
+			SealedChains::<T>::get(id).map_or(0, |chain| chain.len()) == (c - 1) as usize,
+			"didn't store the remaining ciphertexts"
+		);
+	}
+
+	set_agenda_ordering {
+		let when = BLOCK_NUMBER.into();
+	}: _(frame_system::RawOrigin::Root, when, TaskOrdering::Shuffled)
+	verify {
+		ensure!(
+			AgendaOrdering::<T>::get(when) == TaskOrdering::Shuffled,
+			"didn't set the ordering"
+		);
+	}
+
+	decrypt_sealed {
+		let l in 1 .. (T::Preimages::MAX_LENGTH as u32);
+		let target: BlockNumberFor<T> = BLOCK_NUMBER.into();
+		// a ciphertext of `l` bytes, give or take the length prefix of the remark
+		let empty: <T as Config>::RuntimeCall = SystemCall::remark { remark: vec![] }.into();
+		let overhead = T::BenchmarkHelper::seal(&empty.encode(), target).len() as u32;
+		let call: <T as Config>::RuntimeCall =
+			SystemCall::remark { remark: vec![0u8; l.saturating_sub(overhead) as usize] }.into();
+		let bytes = T::BenchmarkHelper::seal(&call.encode(), target);
+		// the worst case, where the ciphertext is fetched from the preimage provider
+		let hash = T::Preimages::note(bytes.clone().into())?;
+		let ciphertext = SealedCiphertext::Lookup { hash, len: bytes.len() as u32 };
+		T::BenchmarkHelper::publish_pulse(target);
+		let mut result = Err(TimelockError::DecryptionFailed);
+	}: {
+		result = Scheduler::<T>::fetch_sealed(&ciphertext)
+			.ok_or(TimelockError::MalformedCiphertext)
+			.and_then(|bytes| Scheduler::<T>::decrypt_sealed(&bytes, target));
+	}
+	verify {
+		ensure!(result.is_ok(), "the sealed call could not be decrypted");
 	}
 
 	impl_benchmark_test_suite!(Scheduler, crate::mock::new_test_ext(), crate::mock::Test);
@@ -78,6 +78,7 @@
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -91,8 +92,9 @@ use frame_support::{
 	ensure,
 	traits::{
 		schedule::{self, DispatchTime, MaybeHashed},
-		Bounded, CallerTrait, EnsureOrigin, Get, IsType, OriginTrait,
-		PrivilegeCmp, QueryPreimage, StorageVersion, StorePreimage,
+		Bounded, CallerTrait, Currency, EnsureOrigin, Get, IsType, OriginTrait,
+		PrivilegeCmp, QueryPreimage, ReservableCurrency, StorageVersion,
+		StorePreimage,
 	},
 	weights::{Weight, WeightMeter},
 };
@@ -104,10 +106,10 @@ use scale_info::TypeInfo;
 use sp_io::hashing::blake2_256;
 use sp_runtime::{
 	traits::{BadOrigin, Dispatchable, One, Saturating, Zero, ConstU32},
-	BoundedVec, DispatchError, RuntimeDebug,
+	BoundedVec, DispatchError, RandomNumberGenerator, RuntimeDebug,
 };
 use sp_std::{borrow::Borrow, cmp::Ordering, marker::PhantomData, prelude::*};
-use pallet_randomness_beacon::{TimelockEncryptionProvider};
+use pallet_randomness_beacon::{BeaconRandomness, TimelockEncryptionProvider, TimelockError};
 pub use pallet::*;
 
 /// Just a simple index for naming period tasks.
@@ -121,10 +123,57 @@ pub type CallOrHashOf<T> =
 pub type BoundedCallOf<T> =
 	Bounded<<T as Config>::RuntimeCall, <T as frame_system::Config>::Hashing>;
 
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
 // TODO: ciphertexts can't exceed 4048 (arbitratily)
 // we need to determine a better upper bound for this
 pub type Ciphertext = BoundedVec<u8, ConstU32<4048>>;
 
+/// The ciphertext of a sealed task.
+///
+/// Like a [`Bounded`] call, a ciphertext that is too large to be stored in the agenda can be
+/// noted with the preimage provider and referenced by its hash. It is fetched when the task is
+/// serviced and the preimage is dropped once the task no longer needs it.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub enum SealedCiphertext<Hash> {
+	/// The ciphertext is stored in the agenda.
+	Inline(Ciphertext),
+	/// The ciphertext is the preimage of `hash`, which is `len` bytes long.
+	Lookup { hash: Hash, len: u32 },
+}
+
+impl<Hash: AsRef<[u8]> + Clone> SealedCiphertext<Hash> {
+	/// The name of a sealed task that is not given one explicitly, when scheduled by `origin`.
+	///
+	/// The name depends on the origin as well as the ciphertext, so that no one can take the
+	/// name of a ciphertext they saw before it was scheduled.
+	pub fn task_name<Origin: Encode>(&self, origin: &Origin) -> TaskName {
+		match self {
+			Self::Inline(ciphertext) => (origin, &ciphertext[..]).using_encoded(blake2_256),
+			Self::Lookup { hash, .. } => (origin, hash.as_ref()).using_encoded(blake2_256),
+		}
+	}
+
+	/// The length of the ciphertext in bytes.
+	pub fn ciphertext_len(&self) -> u32 {
+		match self {
+			Self::Inline(ciphertext) => ciphertext.len() as u32,
+			Self::Lookup { len, .. } => *len,
+		}
+	}
+
+	/// The hash of the preimage holding the ciphertext, if it is not stored inline.
+	pub fn lookup_hash(&self) -> Option<Hash> {
+		match self {
+			Self::Inline(_) => None,
+			Self::Lookup { hash, .. } => Some(hash.clone()),
+		}
+	}
+}
+
+pub type SealedCiphertextOf<T> = SealedCiphertext<<T as frame_system::Config>::Hash>;
+
 /// Information regarding an item to be executed in the future.
 #[cfg_attr(any(feature = "std", test), derive(PartialEq, Eq))]
 #[derive(Clone, RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo)]
@@ -144,15 +193,95 @@ pub struct Scheduled<Name, Call, Ciphertext, BlockNumber, PalletsOrigin, Account
 	_phantom: PhantomData<AccountId>,
 }
 
+/// The reason a sealed task expired before its call could be recovered.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub enum SealedTaskExpiry {
+	/// The beacon had not yet produced a pulse for the target block.
+	BeaconBehind,
+	/// The beacon moved past the target block without producing a pulse for it.
+	MissingPulse,
+}
+
+/// The order in which the tasks of an agenda are serviced.
+///
+/// Orderings are listed from the loosest to the tightest. An agenda can only be moved to a
+/// tighter ordering, so that no one can undo the shuffling of an agenda others rely on.
+#[derive(
+	Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug, Encode, Decode,
+	MaxEncodedLen, TypeInfo,
+)]
+pub enum TaskOrdering {
+	/// Tasks are serviced by priority, then in the order they were scheduled.
+	#[default]
+	Priority,
+	/// Tasks are serviced by priority, and sealed tasks of equal priority in an order drawn
+	/// from the beacon pulse for the agenda's block.
+	///
+	/// Sealed tasks wait for that pulse for up to `SealedTaskExpiry` blocks, after which they
+	/// are serviced by priority. Other tasks are never held back.
+	Shuffled,
+}
+
+/// Information about a sealed task that is not known from its ciphertext.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct SealedTaskInfo<AccountId, Balance, BlockNumber> {
+	/// The account that placed a deposit for the ciphertext and the amount held, if any.
+	pub deposit: Option<(AccountId, Balance)>,
+	/// The maximum weight the decrypted call is allowed to consume.
+	pub max_weight: Weight,
+	/// The block the ciphertext is locked to, which is kept if the task is rescheduled.
+	pub target: BlockNumber,
+}
+
+pub type SealedTaskInfoOf<T> = SealedTaskInfo<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	BlockNumberFor<T>,
+>;
+
 pub type ScheduledOf<T> = Scheduled<
 	TaskName,
 	BoundedCallOf<T>,
-	Ciphertext,
+	SealedCiphertextOf<T>,
 	BlockNumberFor<T>,
 	<T as Config>::PalletsOrigin,
 	<T as frame_system::Config>::AccountId,
 >;
 
+/// A task in the agenda, as reported by the scheduler runtime API.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct AgendaEntry<BlockNumber, PalletsOrigin> {
+	/// The block and index of the task in its agenda.
+	pub address: TaskAddress<BlockNumber>,
+	/// The name of the task, if it has one.
+	pub maybe_id: Option<TaskName>,
+	/// The priority of the task.
+	pub priority: schedule::Priority,
+	/// The origin the call is dispatched with.
+	pub origin: PalletsOrigin,
+	/// The period of the task, if it is periodic.
+	pub maybe_periodic: Option<schedule::Period<BlockNumber>>,
+	/// Whether the call is sealed until the pulse for its target block.
+	pub sealed: bool,
+	/// The length of the ciphertext of a sealed task.
+	pub ciphertext_len: Option<u32>,
+}
+
+/// The maximum number of blocks whose agendas are returned by a single query.
+pub const MAX_AGENDA_QUERY_BLOCKS: u32 = 1_000;
+
+/// Helper to benchmark the decryption of sealed calls.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<BlockNumber> {
+	/// Timelock encrypt `message` to the pulse of `target`.
+	fn seal(message: &[u8], target: BlockNumber) -> Vec<u8>;
+
+	/// Make the beacon produce the pulse of `target`, so the messages sealed for it can be
+	/// decrypted.
+	fn publish_pulse(target: BlockNumber);
+}
+
 // expected that WeightInfo is a struct and not a type
 pub(crate) trait MarginalWeightInfo: WeightInfo {
 	fn service_task(maybe_lookup_len: Option<usize>, named: bool, periodic: bool) -> Weight {
@@ -179,7 +308,7 @@ pub mod pallet {
 	use frame_system::pallet_prelude::*;
 
 	/// The current storage version.
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
@@ -216,6 +345,12 @@ pub mod pallet {
 		/// Required origin to schedule or cancel calls.
 		type ScheduleOrigin: EnsureOrigin<<Self as system::Config>::RuntimeOrigin>;
 
+		/// Required origin to set the ordering of an agenda.
+		///
+		/// A shuffled agenda holds back its sealed tasks until the beacon pulse for its block is
+		/// known, so this should be an origin that others cannot use to delay their tasks.
+		type OrderingOrigin: EnsureOrigin<<Self as system::Config>::RuntimeOrigin>;
+
 		/// Compare the privileges of origins.
 		///
 		/// This will be used when canceling a task, to ensure that the origin that tries
@@ -241,6 +376,39 @@ pub mod pallet {
 
 		/// something that can decrypt messages locked for the current slot
 		type TlockProvider: TimelockEncryptionProvider<BlockNumberFor<Self>>;
+
+		/// The number of blocks past its target block that a sealed task waits for the beacon
+		/// before it expires.
+		///
+		/// A sealed task can only be decrypted once the beacon has produced a pulse for its target
+		/// block. If the beacon falls behind, the task is retried each block until this window
+		/// elapses, after which it is dropped and `SealedTaskExpired` is emitted.
+		#[pallet::constant]
+		type SealedTaskExpiry: Get<BlockNumberFor<Self>>;
+
+		/// The currency used to hold deposits for sealed tasks.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The deposit held per byte of ciphertext that the scheduler stores for a sealed task.
+		///
+		/// The deposit is held from the signer when the task is scheduled and released once the
+		/// task leaves the agenda, whether it was executed, canceled or aborted.
+		#[pallet::constant]
+		type SealedDepositPerByte: Get<BalanceOf<Self>>;
+
+		/// The maximum number of occurrences of a periodic sealed task.
+		///
+		/// Each occurrence carries its own ciphertext, locked to the block it is scheduled for.
+		#[pallet::constant]
+		type MaxSealedOccurrences: Get<u32>;
+
+		/// The randomness of the beacon pulses, used to shuffle agendas that use
+		/// [`TaskOrdering::Shuffled`].
+		type Randomness: BeaconRandomness<Self::Hash, BlockNumberFor<Self>>;
+
+		/// Helper to benchmark the decryption of sealed tasks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<BlockNumberFor<Self>>;
 	}
 
 	#[pallet::storage]
@@ -264,6 +432,27 @@ pub mod pallet {
 	pub(crate) type Lookup<T: Config> =
 		StorageMap<_, Twox64Concat, TaskName, TaskAddress<BlockNumberFor<T>>>;
 
+	/// The deposit and declared weight of each sealed task, keyed by the task name.
+	#[pallet::storage]
+	pub type SealedTasks<T: Config> = StorageMap<_, Twox64Concat, TaskName, SealedTaskInfoOf<T>>;
+
+	/// The ciphertexts of the remaining occurrences of a periodic sealed task, in order.
+	///
+	/// The ciphertext of the next occurrence is moved into the task once the current one has
+	/// been serviced.
+	#[pallet::storage]
+	pub type SealedChains<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		TaskName,
+		BoundedVec<SealedCiphertextOf<T>, T::MaxSealedOccurrences>,
+	>;
+
+	/// The order in which the tasks of an agenda are serviced, if not by priority and index.
+	#[pallet::storage]
+	pub type AgendaOrdering<T: Config> =
+		StorageMap<_, Twox64Concat, BlockNumberFor<T>, TaskOrdering, ValueQuery>;
+
 	/// Events type.
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -284,6 +473,28 @@ pub mod pallet {
 		PeriodicFailed { task: TaskAddress<BlockNumberFor<T>>, id: Option<TaskName> },
 		/// The given task can never be executed since it is overweight.
 		PermanentlyOverweight { task: TaskAddress<BlockNumberFor<T>>, id: Option<TaskName> },
+		/// The call of the given sealed task could not be recovered so the task has been aborted.
+		SealedTaskDecryptionFailed {
+			task: TaskAddress<BlockNumberFor<T>>,
+			id: Option<TaskName>,
+			error: TimelockError,
+		},
+		/// The given sealed task could not be decrypted within the expiry window.
+		SealedTaskExpired {
+			task: TaskAddress<BlockNumberFor<T>>,
+			id: Option<TaskName>,
+			reason: SealedTaskExpiry,
+		},
+		/// The decrypted call of the given sealed task exceeds its declared weight so the task
+		/// has been aborted.
+		SealedTaskOverweight {
+			task: TaskAddress<BlockNumberFor<T>>,
+			id: Option<TaskName>,
+			max_weight: Weight,
+			call_weight: Weight,
+		},
+		/// The order in which the tasks of an agenda are serviced was set.
+		AgendaOrderingSet { when: BlockNumberFor<T>, ordering: TaskOrdering },
 	}
 
 	#[pallet::error]
@@ -298,6 +509,19 @@ pub mod pallet {
 		RescheduleNoChange,
 		/// Attempt to use a non-named function on a named task.
 		Named,
+		/// The declared weight of a sealed task exceeds the maximum weight of the scheduler.
+		SealedWeightTooHigh,
+		/// The task is not a sealed task.
+		NotSealed,
+		/// A sealed task cannot be moved before the block its ciphertext is locked to.
+		RescheduleBeforeTarget,
+		/// A sealed task needs one ciphertext per occurrence and a non-zero period if it has more
+		/// than one occurrence.
+		InvalidSealedChain,
+		/// The ordering of an agenda can only be tightened.
+		OrderingLoosened,
+		/// A sealed task can only be moved with `reschedule_sealed`.
+		Sealed,
 	}
 
 	#[pallet::hooks]
@@ -426,24 +650,126 @@ pub mod pallet {
 		}
 
 		/// Anonymously schedule a timelocked task.
+		///
+		/// The ciphertext is either given inline or as the hash of a noted preimage, which allows
+		/// for calls too large to be stored in the agenda. A deposit of `SealedDepositPerByte`
+		/// for each byte the scheduler stores is held from a signed origin until the task leaves
+		/// the agenda. The decrypted call is rejected if its
+		/// weight exceeds `max_weight`.
 		#[pallet::call_index(6)]
 		#[pallet::weight(<T as Config>::WeightInfo::schedule_sealed(T::MaxScheduledPerBlock::get()))]
 		pub fn schedule_sealed(
 			origin: OriginFor<T>,
 			when: BlockNumberFor<T>,
 			priority: schedule::Priority,
-			ciphertext: Ciphertext,
+			ciphertext: SealedCiphertextOf<T>,
+			max_weight: Weight,
 		) -> DispatchResult {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
+			// the deposit is held from the signer, other origins schedule without one
+			let maybe_depositor = system::ensure_signed(origin.clone()).ok();
 			let origin = <T as Config>::RuntimeOrigin::from(origin);
 			Self::do_schedule_sealed(
 				DispatchTime::At(when),
 				priority,
 				origin.caller().clone(),
 				ciphertext,
+				max_weight,
+				maybe_depositor,
 			)?;
 			Ok(())
 		}
+
+		/// Schedule a named timelocked task, which may be periodic.
+		///
+		/// One ciphertext is given per occurrence. The first is locked to `when` and each
+		/// following one to `period` blocks after the previous. A deposit is held for the bytes of
+		/// all ciphertexts until the last occurrence leaves the agenda.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as Config>::WeightInfo::schedule_named_sealed(
+			T::MaxScheduledPerBlock::get(),
+			ciphertexts.len() as u32,
+		))]
+		pub fn schedule_named_sealed(
+			origin: OriginFor<T>,
+			id: TaskName,
+			when: BlockNumberFor<T>,
+			maybe_period: Option<BlockNumberFor<T>>,
+			priority: schedule::Priority,
+			ciphertexts: BoundedVec<SealedCiphertextOf<T>, T::MaxSealedOccurrences>,
+			max_weight: Weight,
+		) -> DispatchResult {
+			T::ScheduleOrigin::ensure_origin(origin.clone())?;
+			// the deposit is held from the signer, other origins schedule without one
+			let maybe_depositor = system::ensure_signed(origin.clone()).ok();
+			let origin = <T as Config>::RuntimeOrigin::from(origin);
+			Self::do_schedule_named_sealed(
+				id,
+				DispatchTime::At(when),
+				maybe_period,
+				priority,
+				origin.caller().clone(),
+				ciphertexts.into_inner(),
+				max_weight,
+				maybe_depositor,
+			)?;
+			Ok(())
+		}
+
+		/// Cancel a sealed task by its name.
+		///
+		/// A task scheduled with `schedule_sealed` is named by [`SealedCiphertext::task_name`]
+		/// for the origin that scheduled it.
+		#[pallet::call_index(7)]
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_sealed(T::MaxScheduledPerBlock::get()))]
+		pub fn cancel_sealed(origin: OriginFor<T>, id: TaskName) -> DispatchResult {
+			T::ScheduleOrigin::ensure_origin(origin.clone())?;
+			let origin = <T as Config>::RuntimeOrigin::from(origin);
+			Self::do_cancel_sealed(Some(origin.caller().clone()), id)?;
+			Ok(())
+		}
+
+		/// Move a sealed task to another future block.
+		///
+		/// The ciphertext stays locked to the block it was encrypted for, so the task can only
+		/// be moved to that block or later.
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as Config>::WeightInfo::reschedule_sealed(T::MaxScheduledPerBlock::get()))]
+		pub fn reschedule_sealed(
+			origin: OriginFor<T>,
+			id: TaskName,
+			when: BlockNumberFor<T>,
+		) -> DispatchResult {
+			T::ScheduleOrigin::ensure_origin(origin.clone())?;
+			let origin = <T as Config>::RuntimeOrigin::from(origin);
+			Self::do_reschedule_sealed(
+				Some(origin.caller().clone()),
+				id,
+				DispatchTime::At(when),
+			)?;
+			Ok(())
+		}
+
+		/// Set the order in which the tasks of the agenda at `when` are serviced.
+		///
+		/// With [`TaskOrdering::Shuffled`], sealed tasks of equal priority are serviced in an
+		/// order drawn from the beacon pulse for `when`, so it cannot be known when the tasks are
+		/// scheduled. The ordering can only be tightened, so a shuffled agenda stays shuffled.
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_agenda_ordering())]
+		pub fn set_agenda_ordering(
+			origin: OriginFor<T>,
+			when: BlockNumberFor<T>,
+			ordering: TaskOrdering,
+		) -> DispatchResult {
+			T::OrderingOrigin::ensure_origin(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(when > now, Error::<T>::TargetBlockNumberInPast);
+			ensure!(ordering >= AgendaOrdering::<T>::get(when), Error::<T>::OrderingLoosened);
+			AgendaOrdering::<T>::insert(when, ordering);
+			Self::deposit_event(Event::AgendaOrderingSet { when, ordering });
+			Ok(())
+		}
 	}
 }
 
@@ -456,7 +782,7 @@ impl<T: Config> Pallet<T> {
 					Scheduled<
 						TaskName,
 						BoundedCallOf<T>,
-						Ciphertext,
+						SealedCiphertextOf<T>,
 						BlockNumberFor<T>,
 						OldOrigin,
 						T::AccountId,
@@ -473,7 +799,7 @@ impl<T: Config> Pallet<T> {
 							maybe_id: schedule.maybe_id,
 							priority: schedule.priority,
 							maybe_call: schedule.maybe_call,
-							maybe_ciphertext: None,
+							maybe_ciphertext: schedule.maybe_ciphertext,
 							maybe_periodic: schedule.maybe_periodic,
 							origin: schedule.origin.into(),
 							_phantom: Default::default(),
@@ -484,6 +810,48 @@ impl<T: Config> Pallet<T> {
 		});
 	}
 
+	/// The tasks scheduled from `from` up to and including `to`.
+	///
+	/// At most [`MAX_AGENDA_QUERY_BLOCKS`] agendas are read, starting from `from`.
+	pub fn agenda_entries(
+		from: BlockNumberFor<T>,
+		to: BlockNumberFor<T>,
+	) -> Vec<AgendaEntry<BlockNumberFor<T>, T::PalletsOrigin>> {
+		let mut entries = Vec::new();
+		let mut when = from;
+		let mut blocks = 0;
+		while when <= to && blocks < MAX_AGENDA_QUERY_BLOCKS {
+			let agenda = Agenda::<T>::get(when).into_iter().enumerate();
+			for (index, task) in agenda.filter_map(|(i, task)| task.map(|task| (i, task))) {
+				entries.push(AgendaEntry {
+					address: (when, index as u32),
+					maybe_id: task.maybe_id,
+					priority: task.priority,
+					origin: task.origin,
+					maybe_periodic: task.maybe_periodic,
+					sealed: task.maybe_ciphertext.is_some(),
+					ciphertext_len: task.maybe_ciphertext.map(|c| c.ciphertext_len()),
+				});
+			}
+			if when == to {
+				break
+			}
+			when.saturating_inc();
+			blocks += 1;
+		}
+		entries
+	}
+
+	/// The earliest block whose agenda has not been fully serviced, if any.
+	pub fn incomplete_since() -> Option<BlockNumberFor<T>> {
+		IncompleteSince::<T>::get()
+	}
+
+	/// The address of the task named `id`, if it is scheduled.
+	pub fn task_address(id: TaskName) -> Option<TaskAddress<BlockNumberFor<T>>> {
+		Lookup::<T>::get(id)
+	}
+
 	fn resolve_time(
 		when: DispatchTime<BlockNumberFor<T>>,
 	) -> Result<BlockNumberFor<T>, DispatchError> {
@@ -550,6 +918,7 @@ impl<T: Config> Pallet<T> {
 			Some(_) => {},
 			None => {
 				Agenda::<T>::remove(when);
+				AgendaOrdering::<T>::remove(when);
 			},
 		}
 	}
@@ -610,13 +979,18 @@ impl<T: Config> Pallet<T> {
 			)
 		})?;
 		if let Some(s) = scheduled {
-
-			if s.maybe_ciphertext.is_none() && s.maybe_call.is_some() {
-				T::Preimages::drop(&s.maybe_call.clone().unwrap());
+			// a sealed task may hold the call it was decrypted to if its dispatch was postponed
+			if let Some(ref call) = s.maybe_call {
+				T::Preimages::drop(call);
+			}
+			if let Some(hash) = s.maybe_ciphertext.as_ref().and_then(|c| c.lookup_hash()) {
+				T::Preimages::unrequest(&hash);
 			}
-	
 			if let Some(id) = s.maybe_id {
 				Lookup::<T>::remove(id);
+				if s.maybe_ciphertext.is_some() {
+					Self::release_sealed(&id);
+				}
 			}
 			Self::cleanup_agenda(when);
 			Self::deposit_event(Event::Canceled { when, index });
@@ -638,6 +1012,10 @@ impl<T: Config> Pallet<T> {
 
 		let task = Agenda::<T>::try_mutate(when, |agenda| {
 			let task = agenda.get_mut(index as usize).ok_or(Error::<T>::NotFound)?;
+			ensure!(
+				!matches!(task, Some(Scheduled { maybe_ciphertext: Some(_), .. })),
+				Error::<T>::Sealed
+			);
 			ensure!(!matches!(task, Some(Scheduled { maybe_id: Some(_), .. })), Error::<T>::Named);
 			task.take().ok_or(Error::<T>::NotFound)
 		})?;
@@ -702,7 +1080,17 @@ impl<T: Config> Pallet<T> {
 							) {
 								return Err(BadOrigin.into())
 							}
-							T::Preimages::drop(&s.maybe_call.clone().unwrap());
+							if let Some(ref call) = s.maybe_call {
+								T::Preimages::drop(call);
+							}
+						}
+						// named sealed tasks hold a deposit and may have further occurrences
+						let maybe_ciphertext = s.as_ref().and_then(|s| s.maybe_ciphertext.as_ref());
+						if let Some(ciphertext) = maybe_ciphertext {
+							if let Some(hash) = ciphertext.lookup_hash() {
+								T::Preimages::unrequest(&hash);
+							}
+							Self::release_sealed(&id);
 						}
 						*s = None;
 					}
@@ -725,6 +1113,8 @@ impl<T: Config> Pallet<T> {
 
 		let lookup = Lookup::<T>::get(id);
 		let (when, index) = lookup.ok_or(Error::<T>::NotFound)?;
+		// a sealed task must not be moved before its target, nor have its expiry pushed back
+		ensure!(!SealedTasks::<T>::contains_key(id), Error::<T>::Sealed);
 
 		if new_time == when {
 			return Err(Error::<T>::RescheduleNoChange.into())
@@ -744,24 +1134,208 @@ impl<T: Config> Pallet<T> {
 		when: DispatchTime<BlockNumberFor<T>>,
 		priority: schedule::Priority,
 		origin: T::PalletsOrigin,
-		ciphertext: Ciphertext,
+		ciphertext: SealedCiphertextOf<T>,
+		max_weight: Weight,
+		maybe_depositor: Option<T::AccountId>,
 	) -> Result<TaskAddress<BlockNumberFor<T>>, DispatchError> {
+		let id = ciphertext.task_name(&origin);
+		Self::do_schedule_named_sealed(
+			id,
+			when,
+			None,
+			priority,
+			origin,
+			vec![ciphertext],
+			max_weight,
+			maybe_depositor,
+		)
+	}
+
+	/// schedule a chain of sealed tasks under a name, one ciphertext per occurrence
+	fn do_schedule_named_sealed(
+		id: TaskName,
+		when: DispatchTime<BlockNumberFor<T>>,
+		maybe_period: Option<BlockNumberFor<T>>,
+		priority: schedule::Priority,
+		origin: T::PalletsOrigin,
+		ciphertexts: Vec<SealedCiphertextOf<T>>,
+		max_weight: Weight,
+		maybe_depositor: Option<T::AccountId>,
+	) -> Result<TaskAddress<BlockNumberFor<T>>, DispatchError> {
+		ensure!(
+			max_weight.all_lte(T::MaximumWeight::get()),
+			Error::<T>::SealedWeightTooHigh
+		);
 		let when = Self::resolve_time(when)?;
 
-		let id = blake2_256(&ciphertext[..]);
+		// ensure id it is unique, including among sealed tasks that have not yet been released
+		if Lookup::<T>::contains_key(id) || SealedTasks::<T>::contains_key(id) {
+			return Err(Error::<T>::FailedToSchedule.into())
+		}
+
+		let mut ciphertexts = ciphertexts.into_iter();
+		let ciphertext = ciphertexts.next().ok_or(Error::<T>::InvalidSealedChain)?;
+		let chain: BoundedVec<SealedCiphertextOf<T>, T::MaxSealedOccurrences> = ciphertexts
+			.collect::<Vec<_>>()
+			.try_into()
+			.map_err(|_| Error::<T>::InvalidSealedChain)?;
+		let maybe_periodic = match maybe_period {
+			_ if chain.is_empty() => None,
+			Some(period) if !period.is_zero() => Some((period, chain.len() as u32)),
+			_ => return Err(Error::<T>::InvalidSealedChain.into()),
+		};
+
+		let deposit = match maybe_depositor {
+			Some(who) => {
+				let len = chain.iter().fold(ciphertext.encoded_size(), |len, ciphertext| {
+					len.saturating_add(ciphertext.encoded_size())
+				});
+				let amount = T::SealedDepositPerByte::get().saturating_mul((len as u32).into());
+				T::Currency::reserve(&who, amount)?;
+				Some((who, amount))
+			},
+			None => None,
+		};
 
+		let ciphertext_hash = ciphertext.lookup_hash();
 		let task = Scheduled {
 			maybe_id: Some(id),
 			priority,
 			maybe_call: None,
 			maybe_ciphertext: Some(ciphertext),
-			maybe_periodic: None,
+			maybe_periodic,
 			origin,
 			_phantom: PhantomData,
 		};
-		let res = Self::place_task(when, task).map_err(|x| x.0)?;
+		let res = match Self::place_task(when, task) {
+			Ok(address) => address,
+			Err((error, _)) => {
+				if let Some((who, amount)) = deposit {
+					T::Currency::unreserve(&who, amount);
+				}
+				return Err(error)
+			},
+		};
+		SealedTasks::<T>::insert(id, SealedTaskInfo { deposit, max_weight, target: when });
+		// Request the ciphertexts to be made available.
+		if let Some(hash) = ciphertext_hash {
+			T::Preimages::request(&hash);
+		}
+		chain.iter().filter_map(|c| c.lookup_hash()).for_each(|hash| T::Preimages::request(&hash));
+		if !chain.is_empty() {
+			SealedChains::<T>::insert(id, chain);
+		}
 		Ok(res)
 	}
+
+	fn do_cancel_sealed(origin: Option<T::PalletsOrigin>, id: TaskName) -> DispatchResult {
+		ensure!(SealedTasks::<T>::contains_key(id), Error::<T>::NotSealed);
+		let address = Lookup::<T>::get(id).ok_or(Error::<T>::NotFound)?;
+		Self::do_cancel(origin, address)
+	}
+
+	fn do_reschedule_sealed(
+		origin: Option<T::PalletsOrigin>,
+		id: TaskName,
+		new_time: DispatchTime<BlockNumberFor<T>>,
+	) -> Result<TaskAddress<BlockNumberFor<T>>, DispatchError> {
+		let new_time = Self::resolve_time(new_time)?;
+
+		let info = SealedTasks::<T>::get(id).ok_or(Error::<T>::NotSealed)?;
+		ensure!(new_time >= info.target, Error::<T>::RescheduleBeforeTarget);
+
+		let (when, index) = Lookup::<T>::get(id).ok_or(Error::<T>::NotFound)?;
+		if new_time == when {
+			return Err(Error::<T>::RescheduleNoChange.into())
+		}
+
+		let task = Agenda::<T>::try_mutate(when, |agenda| -> Result<_, DispatchError> {
+			let task = agenda.get_mut(index as usize).ok_or(Error::<T>::NotFound)?;
+			if let (Some(ref o), Some(ref s)) = (origin, task.borrow()) {
+				if matches!(
+					T::OriginPrivilegeCmp::cmp_privilege(o, &s.origin),
+					Some(Ordering::Less) | None
+				) {
+					return Err(BadOrigin.into())
+				}
+			}
+			task.take().ok_or(Error::<T>::NotFound.into())
+		})?;
+		Self::cleanup_agenda(when);
+		Self::deposit_event(Event::Canceled { when, index });
+		Self::place_task(new_time, task).map_err(|x| x.0)
+	}
+
+	/// Move a serviced sealed task on to its next occurrence, or retire it if it has none.
+	///
+	/// The next occurrence is placed at the block its ciphertext is locked to, or the next block
+	/// if that has already passed.
+	fn advance_sealed(
+		now: BlockNumberFor<T>,
+		when: BlockNumberFor<T>,
+		agenda_index: u32,
+		mut task: ScheduledOf<T>,
+	) {
+		if let Some(ref call) = task.maybe_call.take() {
+			T::Preimages::drop(call);
+		}
+		if let Some(hash) = task.maybe_ciphertext.as_ref().and_then(|c| c.lookup_hash()) {
+			T::Preimages::unrequest(&hash);
+		}
+		let id = match task.maybe_id {
+			Some(id) => id,
+			None => return,
+		};
+		let (period, count) = match task.maybe_periodic {
+			Some(periodic) => periodic,
+			None => return Self::release_sealed(&id),
+		};
+		let next = SealedChains::<T>::mutate_exists(id, |maybe_chain| {
+			let chain = maybe_chain.as_mut()?;
+			let next = (!chain.is_empty()).then(|| chain.remove(0));
+			if chain.is_empty() {
+				*maybe_chain = None;
+			}
+			next
+		});
+		let ciphertext = match next {
+			Some(ciphertext) => ciphertext,
+			None => return Self::release_sealed(&id),
+		};
+
+		task.maybe_periodic = if count > 1 { Some((period, count - 1)) } else { None };
+		task.maybe_ciphertext = Some(ciphertext);
+		let target = SealedTasks::<T>::mutate(id, |maybe_info| {
+			maybe_info.as_mut().map(|info| {
+				info.target = info.target.saturating_add(period);
+				info.target
+			})
+		});
+		let wake = target
+			.unwrap_or_else(|| now.saturating_add(period))
+			.max(now.saturating_add(One::one()));
+		if let Err((_, task)) = Self::place_task(wake, task) {
+			Self::release_sealed(&id);
+			Self::deposit_event(Event::PeriodicFailed {
+				task: (when, agenda_index),
+				id: task.maybe_id,
+			});
+		}
+	}
+
+	/// Release the deposit of a sealed task that has left the agenda.
+	fn release_sealed(id: &TaskName) {
+		SealedChains::<T>::take(id)
+			.into_iter()
+			.flatten()
+			.filter_map(|ciphertext| ciphertext.lookup_hash())
+			.for_each(|hash| T::Preimages::unrequest(&hash));
+		if let Some(SealedTaskInfo { deposit: Some((who, amount)), .. }) =
+			SealedTasks::<T>::take(id)
+		{
+			T::Currency::unreserve(&who, amount);
+		}
+	}
 }
 
 enum ServiceTaskError {
@@ -821,6 +1395,18 @@ impl<T: Config> Pallet<T> {
 			})
 			.collect::<Vec<_>>();
 		ordered.sort_by_key(|k| k.1);
+
+		// sealed tasks of a shuffled agenda are held back until the pulse for `when` is known
+		let mut hold_sealed = false;
+		if AgendaOrdering::<T>::get(when) == TaskOrdering::Shuffled {
+			match T::Randomness::random_for(b"scheduler", when) {
+				Some(seed) => Self::shuffle_sealed(seed, &agenda, &mut ordered),
+				None => {
+					let expired = now >= when.saturating_add(T::SealedTaskExpiry::get());
+					hold_sealed = then < when && !expired;
+				},
+			}
+		}
 		let within_limit = weight
 			.try_consume(T::WeightInfo::service_agenda_base(ordered.len() as u32))
 			.is_ok();
@@ -838,26 +1424,99 @@ impl<T: Config> Pallet<T> {
 				Some(t) => t,
 			};
 
-			if let Some(ref ciphertext) = task.maybe_ciphertext {
-				// the task should be delayed until `then` == `when`
-				if then == when  {
-					task.maybe_call = T::TlockProvider::decrypt_at(&ciphertext.clone(), then)
-						.map_err(|_| pallet_randomness_beacon::TimelockError::DecryptionFailed)
-						.and_then(|bare| {
-							if let Ok(call) = <T as Config>::RuntimeCall::decode(&mut bare.message.as_slice()) {
-								Ok(call)
-							} else {
-								Err(pallet_randomness_beacon::TimelockError::DecryptionFailed)
-							}
-						})
-						.and_then(|call| T::Preimages::bound(call)
-						.map_err(|_| pallet_randomness_beacon::TimelockError::DecryptionFailed))
-						.ok();
-				} else {
-					// insert the task back into the agenda and continue
+			if hold_sealed && task.maybe_ciphertext.is_some() {
+				agenda[agenda_index as usize] = Some(task);
+				postponed += 1;
+				continue
+			}
+
+			// a sealed task keeps its decrypted call if its dispatch is postponed, so it is only
+			// decrypted once
+			if let (Some(ciphertext), None) = (&task.maybe_ciphertext, &task.maybe_call) {
+				let info = task.maybe_id.and_then(SealedTasks::<T>::get);
+				// a rescheduled task is still locked to the block it was encrypted for
+				let target = info.as_ref().map_or(when, |info| info.target);
+				let expired = now >= when.saturating_add(T::SealedTaskExpiry::get());
+				// the task can only be decrypted once the beacon has reached `target`
+				if then < target {
+					if expired {
+						Self::expire_sealed(
+							now,
+							when,
+							agenda_index,
+							task,
+							SealedTaskExpiry::BeaconBehind,
+						);
+					} else {
+						// insert the task back into the agenda and continue
+						agenda[agenda_index as usize] = Some(task);
+						postponed += 1;
+					}
+					continue
+				}
+
+				// covers fetching the ciphertext as well as decrypting it
+				let decrypt_weight = T::WeightInfo::decrypt_sealed(ciphertext.ciphertext_len());
+				if weight.try_consume(decrypt_weight).is_err() {
 					agenda[agenda_index as usize] = Some(task);
 					postponed += 1;
-					continue
+					break
+				}
+
+				let bytes = match Self::fetch_sealed(ciphertext) {
+					Some(bytes) => bytes,
+					None => {
+						Self::deposit_event(Event::CallUnavailable {
+							task: (when, agenda_index),
+							id: task.maybe_id,
+						});
+						Self::abort_sealed(now, when, agenda_index, task);
+						continue
+					},
+				};
+
+				match Self::decrypt_sealed(&bytes, target) {
+					Ok((call, call_weight)) => {
+						let max_weight = info
+							.map_or(T::MaximumWeight::get(), |info| info.max_weight);
+						if call_weight.any_gt(max_weight) {
+							T::Preimages::drop(&call);
+							Self::deposit_event(Event::SealedTaskOverweight {
+								task: (when, agenda_index),
+								id: task.maybe_id,
+								max_weight,
+								call_weight,
+							});
+							Self::abort_sealed(now, when, agenda_index, task);
+							continue
+						}
+						task.maybe_call = Some(call);
+					},
+					// the pulse may still be written late, so wait until the task expires
+					Err(TimelockError::MissingPulse) if !expired => {
+						agenda[agenda_index as usize] = Some(task);
+						postponed += 1;
+						continue
+					},
+					Err(TimelockError::MissingPulse) => {
+						Self::expire_sealed(
+							now,
+							when,
+							agenda_index,
+							task,
+							SealedTaskExpiry::MissingPulse,
+						);
+						continue
+					},
+					Err(error) => {
+						Self::deposit_event(Event::SealedTaskDecryptionFailed {
+							task: (when, agenda_index),
+							id: task.maybe_id,
+							error,
+						});
+						Self::abort_sealed(now, when, agenda_index, task);
+						continue
+					},
 				}
 			}
 
@@ -874,11 +1533,13 @@ impl<T: Config> Pallet<T> {
 				task.maybe_periodic.is_some(),
 			);
 			if !weight.can_consume(base_weight) {
+				agenda[agenda_index as usize] = Some(task);
 				postponed += 1;
 				break
 			}
 			let result = Self::service_task(weight, now, when, agenda_index, *executed == 0, task);
 			agenda[agenda_index as usize] = match result {
+				Err((Unavailable, None)) => None,
 				Err((Unavailable, slot)) => {
 					dropped += 1;
 					slot
@@ -897,11 +1558,112 @@ impl<T: Config> Pallet<T> {
 			Agenda::<T>::insert(when, agenda);
 		} else {
 			Agenda::<T>::remove(when);
+			AgendaOrdering::<T>::remove(when);
 		}
 
 		postponed == 0
 	}
 
+	/// The bytes of a sealed ciphertext, fetched from the preimage provider if they are not
+	/// stored inline.
+	fn fetch_sealed(ciphertext: &SealedCiphertextOf<T>) -> Option<Vec<u8>> {
+		match ciphertext {
+			SealedCiphertext::Inline(ciphertext) => Some(ciphertext.to_vec()),
+			SealedCiphertext::Lookup { hash, len } =>
+				T::Preimages::fetch(hash, Some(*len)).ok().map(|bytes| bytes.into_owned()),
+		}
+	}
+
+	/// Recover the call of a sealed task from its ciphertext, along with the call's weight.
+	fn decrypt_sealed(
+		bytes: &[u8],
+		target: BlockNumberFor<T>,
+	) -> Result<(BoundedCallOf<T>, Weight), TimelockError> {
+		let bare = T::TlockProvider::decrypt_at(bytes, target)?;
+		let call = <T as Config>::RuntimeCall::decode(&mut bare.message.as_slice())
+			.map_err(|_| TimelockError::CallDecodeFailure)?;
+		let call_weight = call.get_dispatch_info().weight;
+		T::Preimages::bound(call)
+			.map(|bounded| (bounded, call_weight))
+			.map_err(|_| TimelockError::BoundCallFailure)
+	}
+
+	/// Shuffle the sealed tasks of an agenda that is ordered by priority among the places they
+	/// hold, so that other tasks keep their order.
+	fn shuffle_sealed(
+		seed: T::Hash,
+		agenda: &[Option<ScheduledOf<T>>],
+		ordered: &mut [(u32, schedule::Priority)],
+	) {
+		let places = ordered
+			.iter()
+			.enumerate()
+			.filter(|(_, (index, _))| {
+				agenda[*index as usize]
+					.as_ref()
+					.map_or(false, |task| task.maybe_ciphertext.is_some())
+			})
+			.map(|(place, _)| place)
+			.collect::<Vec<_>>();
+		let mut sealed = places.iter().map(|place| ordered[*place]).collect::<Vec<_>>();
+		Self::shuffle_within_priorities(seed, &mut sealed);
+		for (place, item) in places.into_iter().zip(sealed) {
+			ordered[place] = item;
+		}
+	}
+
+	/// Shuffle the runs of tasks with equal priority in an agenda that is ordered by priority.
+	///
+	/// The `seed` is drawn from the beacon pulse for the agenda's block, which is not known when
+	/// the tasks are scheduled.
+	fn shuffle_within_priorities(seed: T::Hash, ordered: &mut [(u32, schedule::Priority)]) {
+		let mut rng = RandomNumberGenerator::<T::Hashing>::new(seed);
+		let mut start = 0;
+		while start < ordered.len() {
+			let priority = ordered[start].1;
+			let end = ordered[start..]
+				.iter()
+				.position(|k| k.1 != priority)
+				.map_or(ordered.len(), |len| start + len);
+			// Fisher-Yates shuffle of the run `start..end`
+			for i in (start + 1..end).rev() {
+				ordered.swap(i, start + rng.pick_usize(i - start));
+			}
+			start = end;
+		}
+	}
+
+	/// Drop a sealed task that could not be decrypted within its expiry window.
+	fn expire_sealed(
+		now: BlockNumberFor<T>,
+		when: BlockNumberFor<T>,
+		agenda_index: u32,
+		task: ScheduledOf<T>,
+		reason: SealedTaskExpiry,
+	) {
+		Self::deposit_event(Event::SealedTaskExpired {
+			task: (when, agenda_index),
+			id: task.maybe_id,
+			reason,
+		});
+		Self::abort_sealed(now, when, agenda_index, task);
+	}
+
+	/// Skip the current occurrence of a sealed task without dispatching it.
+	///
+	/// Any later occurrences of a periodic sealed task are still scheduled.
+	fn abort_sealed(
+		now: BlockNumberFor<T>,
+		when: BlockNumberFor<T>,
+		agenda_index: u32,
+		task: ScheduledOf<T>,
+	) {
+		if let Some(ref id) = task.maybe_id {
+			Lookup::<T>::remove(id);
+		}
+		Self::advance_sealed(now, when, agenda_index, task);
+	}
+
 	/// Service (i.e. execute) the given task, being careful not to overflow the `weight` counter.
 	///
 	/// This involves:
@@ -928,6 +1690,11 @@ impl<T: Config> Pallet<T> {
 					task: (when, agenda_index),
 					id: task.maybe_id,
 				});
+				// a sealed task cannot be recovered, so it does not stay in the agenda
+				if task.maybe_ciphertext.is_some() {
+					Self::abort_sealed(now, when, agenda_index, task);
+					return Err((Unavailable, None))
+				}
 
 				return Err((Unavailable, Some(task)))
 			},
@@ -941,21 +1708,33 @@ impl<T: Config> Pallet<T> {
 
 		match Self::execute_dispatch(weight, task.origin.clone(), call) {
 			Err(()) if is_first => {
-				T::Preimages::drop(&task.maybe_call.clone().unwrap());
 				Self::deposit_event(Event::PermanentlyOverweight {
 					task: (when, agenda_index),
 					id: task.maybe_id,
 				});
+				if task.maybe_ciphertext.is_some() {
+					Self::abort_sealed(now, when, agenda_index, task);
+					return Err((Unavailable, None))
+				}
+				T::Preimages::drop(&task.maybe_call.clone().unwrap());
 				Err((Unavailable, Some(task)))
 			},
-			Err(()) => Err((Overweight, Some(task))),
+			Err(()) => {
+				// the task stays in its agenda, so it can still be found by name
+				if let Some(id) = task.maybe_id {
+					Lookup::<T>::insert(id, (when, agenda_index));
+				}
+				Err((Overweight, Some(task)))
+			},
 			Ok(result) => {
 				Self::deposit_event(Event::Dispatched {
 					task: (when, agenda_index),
 					id: task.maybe_id,
 					result,
 				});
-				if let &Some((period, count)) = &task.maybe_periodic {
+				if task.maybe_ciphertext.is_some() {
+					Self::advance_sealed(now, when, agenda_index, task);
+				} else if let &Some((period, count)) = &task.maybe_periodic {
 					if count > 1 {
 						task.maybe_periodic = Some((period, count - 1));
 					} else {
@@ -1132,7 +1911,7 @@ impl<T: Config> schedule::v3::Anon<BlockNumberFor<T>, <T as Config>::RuntimeCall
 	}
 }
 
-use schedule::v3::TaskName;
+pub use schedule::v3::TaskName;
 
 impl<T: Config> schedule::v3::Named<BlockNumberFor<T>, <T as Config>::RuntimeCall, T::PalletsOrigin>
 	for Pallet<T>
@@ -0,0 +1,377 @@
+/*
+ * Copyright 2024 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Storage migrations for the scheduler pallet.
+
+use super::*;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+
+const LOG_TARGET: &str = "runtime::scheduler::migration";
+
+/// The storage layout of the upstream `pallet_scheduler` at storage version 4.
+pub mod v4 {
+	use super::*;
+
+	/// A task as stored by the upstream scheduler, which always has a call and never a
+	/// ciphertext.
+	#[derive(Clone, RuntimeDebug, Encode, Decode)]
+	pub struct Scheduled<Name, Call, BlockNumber, PalletsOrigin, AccountId> {
+		pub maybe_id: Option<Name>,
+		pub priority: schedule::Priority,
+		pub call: Call,
+		pub maybe_periodic: Option<schedule::Period<BlockNumber>>,
+		pub origin: PalletsOrigin,
+		pub _phantom: PhantomData<AccountId>,
+	}
+
+	pub type ScheduledV4Of<T> = Scheduled<
+		TaskName,
+		BoundedCallOf<T>,
+		BlockNumberFor<T>,
+		<T as Config>::PalletsOrigin,
+		<T as frame_system::Config>::AccountId,
+	>;
+
+	#[frame_support::storage_alias]
+	pub type Agenda<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BoundedVec<Option<ScheduledV4Of<T>>, <T as Config>::MaxScheduledPerBlock>,
+		ValueQuery,
+	>;
+
+	/// A task as stored by this scheduler at storage version 4, whose ciphertext is always
+	/// stored inline.
+	pub type CiphertextScheduledOf<T> = crate::Scheduled<
+		TaskName,
+		BoundedCallOf<T>,
+		Ciphertext,
+		BlockNumberFor<T>,
+		<T as Config>::PalletsOrigin,
+		<T as frame_system::Config>::AccountId,
+	>;
+
+	#[frame_support::storage_alias]
+	pub type CiphertextAgenda<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BoundedVec<Option<CiphertextScheduledOf<T>>, <T as Config>::MaxScheduledPerBlock>,
+		ValueQuery,
+	>;
+}
+
+pub mod v5 {
+	use super::*;
+
+	/// The blocks whose agendas cannot be decoded in the current layout.
+	///
+	/// `translate` leaves a value it cannot decode in place, so the tasks of such an agenda
+	/// would be silently lost when it is next read.
+	fn undecodable_agendas<T: Config>() -> Vec<BlockNumberFor<T>> {
+		Agenda::<T>::iter_keys().filter(|when| Agenda::<T>::try_get(when).is_err()).collect()
+	}
+
+	/// Report the agendas that could not be migrated, returning how many agendas were read.
+	fn report_undecodable<T: Config>(migrated: u64) -> u64 {
+		let undecodable = undecodable_agendas::<T>();
+		if !undecodable.is_empty() {
+			log::error!(
+				target: LOG_TARGET,
+				"{} agendas could not be decoded and were not migrated: {:?}",
+				undecodable.len(),
+				undecodable,
+			);
+		}
+		migrated.saturating_add(undecodable.len() as u64)
+	}
+
+	/// Migrates the agendas of the upstream scheduler at storage version 4 to version 5.
+	///
+	/// Every task keeps its call and gets no ciphertext. `Lookup` and `IncompleteSince` are
+	/// unchanged, and the storage items for sealed tasks start out empty.
+	///
+	/// The migration only runs while the on-chain storage version is 4. It must not be applied
+	/// to a chain whose agendas already use the sealed task layout.
+	pub struct MigrateV4ToV5<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateV4ToV5<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version != 4 {
+				log::info!(
+					target: LOG_TARGET,
+					"skipping the v5 migration, the on-chain storage version is {:?}",
+					on_chain_version,
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut agendas = 0u64;
+			Agenda::<T>::translate::<
+				BoundedVec<Option<v4::ScheduledV4Of<T>>, T::MaxScheduledPerBlock>,
+				_,
+			>(|_, agenda| {
+				agendas.saturating_inc();
+				Some(BoundedVec::truncate_from(
+					agenda
+						.into_iter()
+						.map(|schedule| {
+							schedule.map(|schedule| Scheduled {
+								maybe_id: schedule.maybe_id,
+								priority: schedule.priority,
+								maybe_call: Some(schedule.call),
+								maybe_ciphertext: None,
+								maybe_periodic: schedule.maybe_periodic,
+								origin: schedule.origin,
+								_phantom: Default::default(),
+							})
+						})
+						.collect::<Vec<_>>(),
+				))
+			});
+			StorageVersion::new(5).put::<Pallet<T>>();
+			log::info!(target: LOG_TARGET, "migrated {} agendas to v5", agendas);
+			let read = report_undecodable::<T>(agendas);
+
+			T::DbWeight::get().reads_writes(agendas + read + 1, agendas + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 4,
+				"the scheduler is not at storage version 4"
+			);
+			ensure!(
+				v4::Agenda::<T>::iter_keys().all(|when| v4::Agenda::<T>::try_get(when).is_ok()),
+				"an agenda cannot be decoded in the upstream v4 layout"
+			);
+			let tasks: Vec<(BlockNumberFor<T>, u32)> = v4::Agenda::<T>::iter()
+				.map(|(when, agenda)| (when, agenda.iter().filter(|t| t.is_some()).count() as u32))
+				.collect();
+			Ok(tasks.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let tasks: Vec<(BlockNumberFor<T>, u32)> = Decode::decode(&mut &state[..])
+				.map_err(|_| "the pre-upgrade state cannot be decoded")?;
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 5,
+				"the scheduler was not moved to storage version 5"
+			);
+			ensure!(
+				undecodable_agendas::<T>().is_empty(),
+				"an agenda cannot be decoded in the v5 layout"
+			);
+			ensure!(
+				Agenda::<T>::iter_keys().count() == tasks.len(),
+				"an agenda was lost in the migration"
+			);
+			for (when, count) in tasks {
+				let agenda = Agenda::<T>::get(when);
+				let migrated = agenda.iter().filter(|t| t.is_some()).count() as u32;
+				ensure!(migrated == count, "a task was lost in the migration");
+				ensure!(
+					agenda.iter().flatten().all(|t| t.maybe_call.is_some() &&
+						t.maybe_ciphertext.is_none()),
+					"a migrated task has no call or a ciphertext"
+				);
+			}
+			Ok(())
+		}
+	}
+
+	/// Record the `Lookup` and `SealedTasks` entries of the sealed task at `(when, index)`, and
+	/// return its name.
+	///
+	/// A task without a name is named as `schedule_sealed` would name it. A task whose name is
+	/// already taken by another task is left as it is and reported.
+	fn index_sealed<T: Config>(
+		when: BlockNumberFor<T>,
+		index: u32,
+		maybe_id: Option<TaskName>,
+		ciphertext: &SealedCiphertextOf<T>,
+		origin: &T::PalletsOrigin,
+	) -> Option<TaskName> {
+		let id = maybe_id.unwrap_or_else(|| ciphertext.task_name(origin));
+		let taken = SealedTasks::<T>::contains_key(id) ||
+			Lookup::<T>::get(id).map_or(false, |address| address != (when, index));
+		if taken {
+			log::error!(
+				target: LOG_TARGET,
+				"the sealed task at {:?} shares its name with another task and was not indexed",
+				(when, index),
+			);
+			return maybe_id
+		}
+		Lookup::<T>::insert(id, (when, index));
+		SealedTasks::<T>::insert(
+			id,
+			SealedTaskInfo { deposit: None, max_weight: T::MaximumWeight::get(), target: when },
+		);
+		Some(id)
+	}
+
+	/// Migrates the agendas of this scheduler at storage version 4 to version 5.
+	///
+	/// At version 4 the ciphertext of a task was always stored inline. Every ciphertext is
+	/// kept as a [`SealedCiphertext::Inline`] and everything else about the task is unchanged,
+	/// except that a sealed task without a name is given one. Each sealed task is recorded in
+	/// `Lookup` and in `SealedTasks`, without a deposit, allowed up to `MaximumWeight` and
+	/// locked to the block it is scheduled for.
+	///
+	/// The migration only runs while the on-chain storage version is 4. Chains that switch
+	/// from the upstream scheduler should run [`MigrateV4ToV5`] instead.
+	pub struct MigrateCiphertextsV4ToV5<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateCiphertextsV4ToV5<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version != 4 {
+				log::info!(
+					target: LOG_TARGET,
+					"skipping the v5 migration, the on-chain storage version is {:?}",
+					on_chain_version,
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut agendas = 0u64;
+			let mut sealed = 0u64;
+			Agenda::<T>::translate::<
+				BoundedVec<Option<v4::CiphertextScheduledOf<T>>, T::MaxScheduledPerBlock>,
+				_,
+			>(|when, agenda| {
+				agendas.saturating_inc();
+				Some(BoundedVec::truncate_from(
+					agenda
+						.into_iter()
+						.enumerate()
+						.map(|(index, schedule)| {
+							schedule.map(|schedule| {
+								let maybe_ciphertext =
+									schedule.maybe_ciphertext.map(SealedCiphertext::Inline);
+								let maybe_id = match maybe_ciphertext {
+									Some(ref ciphertext) => {
+										sealed.saturating_inc();
+										index_sealed::<T>(
+											when,
+											index as u32,
+											schedule.maybe_id,
+											ciphertext,
+											&schedule.origin,
+										)
+									},
+									None => schedule.maybe_id,
+								};
+								Scheduled {
+									maybe_id,
+									priority: schedule.priority,
+									maybe_call: schedule.maybe_call,
+									maybe_ciphertext,
+									maybe_periodic: schedule.maybe_periodic,
+									origin: schedule.origin,
+									_phantom: Default::default(),
+								}
+							})
+						})
+						.collect::<Vec<_>>(),
+				))
+			});
+			StorageVersion::new(5).put::<Pallet<T>>();
+			log::info!(
+				target: LOG_TARGET,
+				"migrated {} agendas with {} sealed tasks to v5",
+				agendas,
+				sealed,
+			);
+			let read = report_undecodable::<T>(agendas);
+
+			T::DbWeight::get()
+				.reads_writes(agendas + read + 2 * sealed + 1, agendas + 2 * sealed + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 4,
+				"the scheduler is not at storage version 4"
+			);
+			ensure!(
+				v4::CiphertextAgenda::<T>::iter_keys()
+					.all(|when| v4::CiphertextAgenda::<T>::try_get(when).is_ok()),
+				"an agenda cannot be decoded in the v4 layout"
+			);
+			let tasks: Vec<(BlockNumberFor<T>, u32, u32)> = v4::CiphertextAgenda::<T>::iter()
+				.map(|(when, agenda)| {
+					let tasks = agenda.iter().flatten();
+					let sealed = tasks.clone().filter(|t| t.maybe_ciphertext.is_some()).count();
+					(when, tasks.count() as u32, sealed as u32)
+				})
+				.collect();
+			Ok(tasks.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let tasks: Vec<(BlockNumberFor<T>, u32, u32)> = Decode::decode(&mut &state[..])
+				.map_err(|_| "the pre-upgrade state cannot be decoded")?;
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 5,
+				"the scheduler was not moved to storage version 5"
+			);
+			ensure!(
+				undecodable_agendas::<T>().is_empty(),
+				"an agenda cannot be decoded in the v5 layout"
+			);
+			ensure!(
+				Agenda::<T>::iter_keys().count() == tasks.len(),
+				"an agenda was lost in the migration"
+			);
+			for (when, count, sealed) in tasks {
+				let agenda = Agenda::<T>::get(when);
+				ensure!(
+					agenda.iter().flatten().count() as u32 == count,
+					"a task was lost in the migration"
+				);
+				ensure!(
+					agenda.iter().flatten().filter(|t| t.maybe_ciphertext.is_some()).count() as u32 ==
+						sealed,
+					"a ciphertext was lost in the migration"
+				);
+				// a sealed task without a name shared it with another task and was reported
+				for (index, task) in agenda.iter().enumerate() {
+					let id = match task {
+						Some(Scheduled { maybe_id: Some(id), maybe_ciphertext: Some(_), .. }) => id,
+						_ => continue,
+					};
+					ensure!(
+						Lookup::<T>::get(id) == Some((when, index as u32)),
+						"a sealed task cannot be found by its name"
+					);
+					ensure!(SealedTasks::<T>::contains_key(id), "a sealed task has no info");
+				}
+			}
+			Ok(())
+		}
+	}
+}
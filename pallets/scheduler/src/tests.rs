@@ -32,23 +32,6 @@ use frame_support::{
 use sp_runtime::traits::Hash;
 use substrate_test_utils::assert_eq_uvec;
 
-use ark_std::{
-	rand::SeedableRng,
-	ops::Mul,
-	One,
-};
-use ark_bls12_381::{Fr, G2Projective as G2};
-use ark_ec::Group;
-use etf_crypto_primitives::utils::convert_to_bytes;
-use etf_crypto_primitives::{
-	client::etf_client::{
-		DefaultEtfClient, 
-		EtfClient
-	},
-	ibe::fullident::BfIbe,
-};
-use rand_chacha::ChaCha20Rng;
-
 #[test]
 #[docify::export]
 fn basic_scheduling_works() {
@@ -1813,81 +1796,29 @@ fn unavailable_call_is_detected() {
 #[test]
 #[docify::export]
 fn timelock_basic_scheduling_works() {
-	let mut rng = ChaCha20Rng::from_seed([4;32]);
-
-	let ids = vec![
-		4u64.to_string().as_bytes().to_vec(), 
-	];
-	let t = 1;
-
-	let ibe_pp: G2 = G2::generator().into();
-	let s = Fr::one();
-	let p_pub: G2 = ibe_pp.mul(s).into();
-
-	let ibe_pp_bytes = convert_to_bytes::<G2, 96>(ibe_pp);
-	let p_pub_bytes = convert_to_bytes::<G2, 96>(p_pub);
-
-	// Q: how can we mock the decryption trait so that we can d1o whatever?
-	// probably don't really need to perform decryption here?
 	new_test_ext().execute_with(|| {
-
-		let _ = Etf::set_ibe_params(
-			// RuntimeOrigin::root(), 
-			&vec![], 
-			&ibe_pp_bytes.into(), 
-			&p_pub_bytes.into()
-		);
-
 		// Call to schedule
 		let call =
 			RuntimeCall::Logger(LoggerCall::log { i: 42, weight: Weight::from_parts(10, 0) });
-
-		// // then we convert to bytes and encrypt the call
-		let ct: etf_crypto_primitives::client::etf_client::AesIbeCt = 
-			DefaultEtfClient::<BfIbe>::encrypt(
-				ibe_pp_bytes.to_vec(),
-				p_pub_bytes.to_vec(),
-				&call.encode(),
-				ids,
-				t,
-				&mut rng,
-			).unwrap();
-
-
-		let mut bounded_ct: BoundedVec<u8, ConstU32<512>> = BoundedVec::new();
-		ct.aes_ct.ciphertext.iter().enumerate().for_each(|(idx, i)| {
-			let _= bounded_ct.try_insert(idx, *i);
-		});
-
-		let mut bounded_nonce: BoundedVec<u8, ConstU32<96>> = BoundedVec::new();
-		ct.aes_ct.nonce.iter().enumerate().for_each(|(idx, i)| {
-			let _= bounded_nonce.try_insert(idx, *i);
-		});
-
-		let mut bounded_capsule: BoundedVec<u8, ConstU32<512>> = BoundedVec::new();
-		// assumes we only care about a single point in the future
-		ct.etf_ct[0].iter().enumerate().for_each(|(idx, i)| {
-			let _= bounded_capsule.try_insert(idx, *i);
-		});
-
-		let ciphertext = Ciphertext {
-			ciphertext: bounded_ct,
-			nonce: bounded_nonce,
-			capsule: bounded_capsule,
-		};
+		// then we encrypt the call for the pulse of the 4th block
+		let ciphertext = seal(4, &call.encode());
 
 		// Schedule call to be executed at the 4th block
 		assert_ok!(Scheduler::do_schedule_sealed(
 			DispatchTime::At(4),
 			127,
 			root(),
-			ciphertext,
+			SealedCiphertext::Inline(ciphertext),
+			Weight::from_parts(100, 0),
+			None,
 		));
 
 		// `log` runtime call should not have executed yet
 		run_to_block(3);
 		assert!(logger::log().is_empty());
 
+		// the beacon produces the pulse for block 4
+		Latest::set(4);
 		run_to_block(4);
 		// `log` runtime call should have executed at block 4
 		assert_eq!(logger::log(), vec![(root(), 42u32)]);
@@ -1901,253 +1832,922 @@ fn timelock_basic_scheduling_works() {
 #[test]
 #[docify::export]
 fn timelock_undecryptable_ciphertext_no_execution() {
-	let mut rng = ChaCha20Rng::from_seed([4;32]);
-
-	let bad_ids = vec![
-		3u64.to_string().as_bytes().to_vec(), 
-	];
-
-	let t = 1;
-
-	let ibe_pp: G2 = G2::generator().into();
-	let s = Fr::one();
-	let p_pub: G2 = ibe_pp.mul(s).into();
-
-	let ibe_pp_bytes = convert_to_bytes::<G2, 96>(ibe_pp);
-	let p_pub_bytes = convert_to_bytes::<G2, 96>(p_pub);
-
 	new_test_ext().execute_with(|| {
-		let _ = Etf::set_ibe_params(
-			// RuntimeOrigin::root(), 
-			&vec![], 
-			&ibe_pp_bytes.into(), 
-			&p_pub_bytes.into()
-		);
-
-
 		// Call to schedule
 		let call =
 			RuntimeCall::Logger(LoggerCall::log { i: 42, weight: Weight::from_parts(10, 0) });
-
-		// encrypts the ciphertext for the wrong identity
-		let ct: etf_crypto_primitives::client::etf_client::AesIbeCt = 
-			DefaultEtfClient::<BfIbe>::encrypt(
-				ibe_pp_bytes.to_vec(),
-				p_pub_bytes.to_vec(),
-				&call.encode(),
-				bad_ids,
-				t,
-				&mut rng,
-			).unwrap();
-
-
-		let mut bounded_ct: BoundedVec<u8, ConstU32<512>> = BoundedVec::new();
-		ct.aes_ct.ciphertext.iter().enumerate().for_each(|(idx, i)| {
-			let _= bounded_ct.try_insert(idx, *i);
-		});
-
-		let mut bounded_nonce: BoundedVec<u8, ConstU32<96>> = BoundedVec::new();
-		ct.aes_ct.nonce.iter().enumerate().for_each(|(idx, i)| {
-			let _= bounded_nonce.try_insert(idx, *i);
-		});
-
-		let mut bounded_capsule: BoundedVec<u8, ConstU32<512>> = BoundedVec::new();
-		// assumes we only care about a single point in the future
-		ct.etf_ct[0].iter().enumerate().for_each(|(idx, i)| {
-			let _= bounded_capsule.try_insert(idx, *i);
-		});
-
-		let ciphertext = Ciphertext {
-			ciphertext: bounded_ct,
-			nonce: bounded_nonce,
-			capsule: bounded_capsule,
-		};
+		// encrypts the ciphertext for the wrong block
+		let ciphertext = seal(3, &call.encode());
 
 		// Schedule call to be executed at the 4th block
 		assert_ok!(Scheduler::do_schedule_sealed(
 			DispatchTime::At(4),
 			127,
 			root(),
-			ciphertext,
+			SealedCiphertext::Inline(ciphertext),
+			Weight::from_parts(100, 0),
+			None,
 		));
 
 		// `log` runtime call should not have executed yet
 		run_to_block(3);
 		assert!(logger::log().is_empty());
 
+		Latest::set(4);
 		run_to_block(4);
 		// `log` runtime call should NOT have executed at block 4
 		assert!(logger::log().is_empty());
+		assert!(matches!(
+			System::events().last().unwrap().event,
+			RuntimeEvent::Scheduler(crate::Event::SealedTaskDecryptionFailed {
+				task: (4, 0),
+				error: TimelockError::DecryptionFailed,
+				..
+			})
+		));
 	});
 }
 
 #[test]
 #[docify::export]
 fn timelock_undecodable_runtime_call_no_execution() {
-	let mut rng = ChaCha20Rng::from_seed([4;32]);
+	new_test_ext().execute_with(|| {
+		// the ciphertext decrypts, but not to a call
+		let ciphertext = seal(4, &b"bad-call-data".encode());
 
-	let ids = vec![
-		4u64.to_string().as_bytes().to_vec(), 
-	];
-	let t = 1;
+		// Schedule call to be executed at the 4th block
+		assert_ok!(Scheduler::do_schedule_sealed(
+			DispatchTime::At(4),
+			127,
+			root(),
+			SealedCiphertext::Inline(ciphertext),
+			Weight::from_parts(100, 0),
+			None,
+		));
 
-	let ibe_pp: G2 = G2::generator().into();
-	let s = Fr::one();
-	let p_pub: G2 = ibe_pp.mul(s).into();
+		// `log` runtime call should not have executed yet
+		run_to_block(3);
+		assert!(logger::log().is_empty());
 
-	let ibe_pp_bytes = convert_to_bytes::<G2, 96>(ibe_pp);
-	let p_pub_bytes = convert_to_bytes::<G2, 96>(p_pub);
+		Latest::set(4);
+		run_to_block(4);
+		// `log` runtime call should NOT have executed at block 4
+		assert!(logger::log().is_empty());
+		// and the task is aborted with the reason it could not be recovered
+		assert!(matches!(
+			System::events().last().unwrap().event,
+			RuntimeEvent::Scheduler(crate::Event::SealedTaskDecryptionFailed {
+				task: (4, 0),
+				error: TimelockError::CallDecodeFailure,
+				..
+			})
+		));
+		assert!(Agenda::<Test>::get(4).iter().all(|task| task.is_none()));
+	});
+}
 
+#[test]
+#[docify::export]
+fn timelock_cancel_works() {
 	new_test_ext().execute_with(|| {
-		let _ = Etf::set_ibe_params(
-			// RuntimeOrigin::root(), 
-			&vec![], 
-			&ibe_pp_bytes.into(), 
-			&p_pub_bytes.into()
-		);
-
-
 		// Call to schedule
-		// let call =
-		// 	RuntimeCall::Logger(LoggerCall::log { i: 42, weight: Weight::from_parts(10, 0) });
-
-		// encrypts the ciphertext for the wrong identity
-		let ct: etf_crypto_primitives::client::etf_client::AesIbeCt = 
-			DefaultEtfClient::<BfIbe>::encrypt(
-				ibe_pp_bytes.to_vec(),
-				p_pub_bytes.to_vec(),
-				&b"bad-call-data".encode(),
-				ids,
-				t,
-				&mut rng,
-			).unwrap();
-
-
-		let mut bounded_ct: BoundedVec<u8, ConstU32<512>> = BoundedVec::new();
-		ct.aes_ct.ciphertext.iter().enumerate().for_each(|(idx, i)| {
-			let _= bounded_ct.try_insert(idx, *i);
-		});
+		let call =
+			RuntimeCall::Logger(LoggerCall::log { i: 42, weight: Weight::from_parts(10, 0) });
+		let ciphertext = seal(4, &call.encode());
 
-		let mut bounded_nonce: BoundedVec<u8, ConstU32<96>> = BoundedVec::new();
-		ct.aes_ct.nonce.iter().enumerate().for_each(|(idx, i)| {
-			let _= bounded_nonce.try_insert(idx, *i);
-		});
+		// Schedule call to be executed at the 4th block
+		assert_ok!(Scheduler::do_schedule_sealed(
+			DispatchTime::At(4),
+			127,
+			root(),
+			SealedCiphertext::Inline(ciphertext),
+			Weight::from_parts(100, 0),
+			None,
+		));
 
-		let mut bounded_capsule: BoundedVec<u8, ConstU32<512>> = BoundedVec::new();
-		// assumes we only care about a single point in the future
-		ct.etf_ct[0].iter().enumerate().for_each(|(idx, i)| {
-			let _= bounded_capsule.try_insert(idx, *i);
-		});
+		// `log` runtime call should not have executed yet
+		run_to_block(3);
+		assert!(logger::log().is_empty());
 
-		let ciphertext = Ciphertext {
-			ciphertext: bounded_ct,
-			nonce: bounded_nonce,
-			capsule: bounded_capsule,
-		};
+		// now cancel
+		assert_ok!(Scheduler::do_cancel(
+			None, (4, 0),
+		));
 
-		// Schedule call to be executed at the 4th block
+		Latest::set(4);
+		run_to_block(4);
+		assert!(logger::log().is_empty());
+	});
+}
+
+#[test]
+fn sealed_task_expires_when_beacon_is_behind() {
+	new_test_ext().execute_with(|| {
+		// no pulses are ever produced, so the beacon never reaches block 4
+		let ciphertext = SealedCiphertext::Inline(b"sealed-call".to_vec().try_into().unwrap());
 		assert_ok!(Scheduler::do_schedule_sealed(
 			DispatchTime::At(4),
 			127,
 			root(),
 			ciphertext,
+			Weight::from_parts(100, 0),
+			None,
 		));
 
-		// `log` runtime call should not have executed yet
-		run_to_block(3);
+		// the task waits for the beacon until the expiry window elapses
+		run_to_block(8);
+		assert!(logger::log().is_empty());
+		assert!(Agenda::<Test>::get(4).iter().any(|task| task.is_some()));
+
+		// SealedTaskExpiry = 5
+		run_to_block(9);
 		assert!(logger::log().is_empty());
+		assert_eq!(
+			System::events().last().unwrap().event,
+			RuntimeEvent::Scheduler(crate::Event::SealedTaskExpired {
+				task: (4, 0),
+				id: Some(blake2_256(b"sealed-call".as_slice())),
+				reason: SealedTaskExpiry::BeaconBehind,
+			})
+		);
+		assert!(Lookup::<Test>::iter().next().is_none());
+	});
+}
+
+#[test]
+fn sealed_task_weight_cannot_exceed_maximum_weight() {
+	new_test_ext().execute_with(|| {
+		let ciphertext = SealedCiphertext::Inline(b"sealed-call".to_vec().try_into().unwrap());
+		let max_weight = MaximumSchedulerWeight::get().saturating_add(Weight::from_parts(1, 0));
+		assert_noop!(
+			Scheduler::do_schedule_sealed(
+				DispatchTime::At(4),
+				127,
+				root(),
+				ciphertext,
+				max_weight,
+				None,
+			),
+			Error::<Test>::SealedWeightTooHigh,
+		);
+	});
+}
+
+#[test]
+fn cancel_sealed_task_clears_its_info() {
+	new_test_ext().execute_with(|| {
+		let ciphertext = SealedCiphertext::Inline(b"sealed-call".to_vec().try_into().unwrap());
+		let id = ciphertext.task_name(&root());
+		assert_ok!(Scheduler::do_schedule_sealed(
+			DispatchTime::At(4),
+			127,
+			root(),
+			ciphertext.clone(),
+			Weight::from_parts(100, 0),
+			None,
+		));
+		assert_eq!(
+			SealedTasks::<Test>::get(id),
+			Some(SealedTaskInfo {
+				deposit: None,
+				max_weight: Weight::from_parts(100, 0),
+				target: 4,
+			})
+		);
+
+		// the same ciphertext cannot be scheduled twice
+		assert_noop!(
+			Scheduler::do_schedule_sealed(
+				DispatchTime::At(5),
+				127,
+				root(),
+				ciphertext,
+				Weight::from_parts(100, 0),
+				None,
+			),
+			Error::<Test>::FailedToSchedule,
+		);
+
+		assert_ok!(Scheduler::do_cancel(None, (4, 0)));
+		assert!(SealedTasks::<Test>::get(id).is_none());
+		assert!(Lookup::<Test>::get(id).is_none());
+	});
+}
+
+#[test]
+fn permanently_overweight_sealed_task_releases_its_deposit() {
+	new_test_ext().execute_with(|| {
+		let max_weight = MaximumSchedulerWeight::get();
+		let call = RuntimeCall::Logger(LoggerCall::log { i: 42, weight: max_weight });
+		let ciphertext = SealedCiphertext::Inline(seal(4, &call.encode()));
+		let id = ciphertext.task_name(&signed(1));
+		assert_ok!(Scheduler::schedule_sealed(
+			RuntimeOrigin::signed(1),
+			4,
+			127,
+			ciphertext.clone(),
+			max_weight,
+		));
+		assert!(Balances::reserved_balance(1) > 0);
 
+		Latest::set(4);
 		run_to_block(4);
-		// `log` runtime call should NOT have executed at block 4
 		assert!(logger::log().is_empty());
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::Scheduler(crate::Event::PermanentlyOverweight {
+				task: (4, 0),
+				id: Some(id),
+			})));
+		// the task is gone along with its deposit and info
+		assert!(Agenda::<Test>::get(4).is_empty());
+		assert!(SealedTasks::<Test>::get(id).is_none());
+		assert_eq!(Balances::reserved_balance(1), 0);
+
+		// and its id can be used again
+		assert_ok!(Scheduler::schedule_sealed(
+			RuntimeOrigin::signed(1),
+			6,
+			127,
+			ciphertext,
+			max_weight,
+		));
 	});
 }
 
 #[test]
-#[docify::export]
-fn timelock_cancel_works() {
-	let mut rng = ChaCha20Rng::from_seed([4;32]);
+fn sealed_id_cannot_be_reused_while_the_task_is_known() {
+	new_test_ext().execute_with(|| {
+		let ciphertext = SealedCiphertext::Inline(b"sealed-call".to_vec().try_into().unwrap());
+		let id = ciphertext.task_name(&root());
+		// a task that left the lookup but was not released yet
+		SealedTasks::<Test>::insert(
+			id,
+			SealedTaskInfo { deposit: None, max_weight: Weight::from_parts(100, 0), target: 4 },
+		);
+		assert_noop!(
+			Scheduler::schedule_sealed(
+				RuntimeOrigin::root(),
+				4,
+				127,
+				ciphertext,
+				Weight::from_parts(100, 0),
+			),
+			Error::<Test>::FailedToSchedule,
+		);
+	});
+}
 
-	let ids = vec![
-		4u64.to_string().as_bytes().to_vec(), 
-	];
-	let t = 1;
+#[test]
+fn cancel_sealed_works() {
+	new_test_ext().execute_with(|| {
+		let ciphertext = SealedCiphertext::Inline(b"sealed-call".to_vec().try_into().unwrap());
+		let id = ciphertext.task_name(&root());
+		assert_ok!(Scheduler::schedule_sealed(
+			RuntimeOrigin::root(),
+			4,
+			127,
+			ciphertext,
+			Weight::from_parts(100, 0),
+		));
+		assert_eq!(Lookup::<Test>::get(id), Some((4, 0)));
 
-	let ibe_pp: G2 = G2::generator().into();
-	let s = Fr::one();
-	let p_pub: G2 = ibe_pp.mul(s).into();
+		run_to_block(3);
+		// a less privileged origin cannot cancel the task
+		assert_noop!(Scheduler::cancel_sealed(RuntimeOrigin::signed(1), id), BadOrigin);
+		assert_ok!(Scheduler::cancel_sealed(RuntimeOrigin::root(), id));
+		assert!(Lookup::<Test>::get(id).is_none());
+		assert!(SealedTasks::<Test>::get(id).is_none());
+		assert!(Agenda::<Test>::get(4).is_empty());
 
-	let ibe_pp_bytes = convert_to_bytes::<G2, 96>(ibe_pp);
-	let p_pub_bytes = convert_to_bytes::<G2, 96>(p_pub);
+		run_to_block(10);
+		assert!(logger::log().is_empty());
+		// nothing was attempted for the canceled task
+		assert!(!System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::Scheduler(crate::Event::SealedTaskExpired { .. }) |
+				RuntimeEvent::Scheduler(crate::Event::SealedTaskDecryptionFailed { .. })
+		)));
+	});
+}
 
-	// Q: how can we mock the decryption trait so that we can d1o whatever?
-	// probably don't really need to perform decryption here?
+#[test]
+fn schedule_sealed_holds_a_deposit_from_the_signer() {
 	new_test_ext().execute_with(|| {
+		let ciphertext = SealedCiphertext::Inline(b"sealed-call".to_vec().try_into().unwrap());
+		let id = ciphertext.task_name(&signed(1));
+		let deposit = ciphertext.encoded_size() as u64;
+		assert_ok!(Scheduler::schedule_sealed(
+			RuntimeOrigin::signed(1),
+			4,
+			127,
+			ciphertext,
+			Weight::from_parts(100, 0),
+		));
+		assert_eq!(Balances::reserved_balance(1), deposit);
+		assert_eq!(SealedTasks::<Test>::get(id).unwrap().deposit, Some((1, deposit)));
 
-		let _ = Etf::set_ibe_params(
-			// RuntimeOrigin::root(), 
-			&vec![], 
-			&ibe_pp_bytes.into(), 
-			&p_pub_bytes.into()
+		// an origin that may not schedule cannot hold a deposit either
+		assert_noop!(
+			Scheduler::schedule_sealed(
+				RuntimeOrigin::signed(2),
+				4,
+				127,
+				SealedCiphertext::Inline(b"other-call".to_vec().try_into().unwrap()),
+				Weight::from_parts(100, 0),
+			),
+			BadOrigin,
 		);
 
-		// Call to schedule
+		assert_ok!(Scheduler::cancel_sealed(RuntimeOrigin::signed(1), id));
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn sealed_task_name_cannot_be_taken_by_another_origin() {
+	new_test_ext().execute_with(|| {
+		let ciphertext = SealedCiphertext::Inline(b"sealed-call".to_vec().try_into().unwrap());
+		// someone who saw the ciphertext schedules it first
+		assert_ok!(Scheduler::schedule_sealed(
+			RuntimeOrigin::root(),
+			4,
+			127,
+			ciphertext.clone(),
+			Weight::from_parts(100, 0),
+		));
+		assert_ok!(Scheduler::schedule_sealed(
+			RuntimeOrigin::signed(1),
+			4,
+			127,
+			ciphertext.clone(),
+			Weight::from_parts(100, 0),
+		));
+
+		let id = ciphertext.task_name(&signed(1));
+		assert_ne!(id, ciphertext.task_name(&root()));
+		assert_eq!(Lookup::<Test>::get(ciphertext.task_name(&root())), Some((4, 0)));
+		assert_eq!(Lookup::<Test>::get(id), Some((4, 1)));
+		assert_ok!(Scheduler::cancel_sealed(RuntimeOrigin::signed(1), id));
+	});
+}
+
+#[test]
+fn cancel_sealed_rejects_unsealed_tasks() {
+	new_test_ext().execute_with(|| {
 		let call =
 			RuntimeCall::Logger(LoggerCall::log { i: 42, weight: Weight::from_parts(10, 0) });
+		assert_ok!(Scheduler::do_schedule_named(
+			[1u8; 32],
+			DispatchTime::At(4),
+			None,
+			127,
+			root(),
+			Preimage::bound(call).unwrap(),
+		));
+		assert_noop!(
+			Scheduler::cancel_sealed(RuntimeOrigin::root(), [1u8; 32]),
+			Error::<Test>::NotSealed,
+		);
+		assert_noop!(
+			Scheduler::cancel_sealed(RuntimeOrigin::root(), [2u8; 32]),
+			Error::<Test>::NotSealed,
+		);
+	});
+}
 
-		// // then we convert to bytes and encrypt the call
-		let ct: etf_crypto_primitives::client::etf_client::AesIbeCt = 
-			DefaultEtfClient::<BfIbe>::encrypt(
-				ibe_pp_bytes.to_vec(),
-				p_pub_bytes.to_vec(),
-				&call.encode(),
-				ids,
-				t,
-				&mut rng,
-			).unwrap();
-
-
-		let mut bounded_ct: BoundedVec<u8, ConstU32<512>> = BoundedVec::new();
-		ct.aes_ct.ciphertext.iter().enumerate().for_each(|(idx, i)| {
-			let _= bounded_ct.try_insert(idx, *i);
-		});
+#[test]
+fn reschedule_sealed_works() {
+	new_test_ext().execute_with(|| {
+		let ciphertext = SealedCiphertext::Inline(b"sealed-call".to_vec().try_into().unwrap());
+		let id = ciphertext.task_name(&root());
+		assert_ok!(Scheduler::schedule_sealed(
+			RuntimeOrigin::root(),
+			4,
+			127,
+			ciphertext,
+			Weight::from_parts(100, 0),
+		));
 
-		let mut bounded_nonce: BoundedVec<u8, ConstU32<96>> = BoundedVec::new();
-		ct.aes_ct.nonce.iter().enumerate().for_each(|(idx, i)| {
-			let _= bounded_nonce.try_insert(idx, *i);
-		});
+		// the ciphertext is locked to block 4 so it cannot be moved earlier
+		assert_noop!(
+			Scheduler::reschedule_sealed(RuntimeOrigin::root(), id, 3),
+			Error::<Test>::RescheduleBeforeTarget,
+		);
+		assert_noop!(
+			Scheduler::reschedule_sealed(RuntimeOrigin::root(), id, 4),
+			Error::<Test>::RescheduleNoChange,
+		);
+		assert_noop!(
+			Scheduler::reschedule_sealed(RuntimeOrigin::signed(1), id, 6),
+			BadOrigin,
+		);
 
-		let mut bounded_capsule: BoundedVec<u8, ConstU32<512>> = BoundedVec::new();
-		// assumes we only care about a single point in the future
-		ct.etf_ct[0].iter().enumerate().for_each(|(idx, i)| {
-			let _= bounded_capsule.try_insert(idx, *i);
-		});
+		// nor can it be moved as a plain task
+		assert_noop!(
+			Scheduler::do_reschedule_named(id, DispatchTime::At(3)),
+			Error::<Test>::Sealed,
+		);
+		assert_noop!(Scheduler::do_reschedule((4, 0), DispatchTime::At(6)), Error::<Test>::Sealed);
+		assert_noop!(
+			<Scheduler as schedule::v3::Named<_, _, _>>::reschedule_named(
+				id,
+				DispatchTime::At(6),
+			),
+			Error::<Test>::Sealed,
+		);
 
-		let ciphertext = Ciphertext {
-			ciphertext: bounded_ct,
-			nonce: bounded_nonce,
-			capsule: bounded_capsule,
-		};
+		assert_ok!(Scheduler::reschedule_sealed(RuntimeOrigin::root(), id, 6));
+		assert_eq!(Lookup::<Test>::get(id), Some((6, 0)));
+		assert!(Agenda::<Test>::get(4).is_empty());
+		assert!(Agenda::<Test>::get(6)[0].is_some());
+		assert_eq!(SealedTasks::<Test>::get(id).unwrap().target, 4);
+	});
+}
 
-		// Schedule call to be executed at the 4th block
-		assert_ok!(Scheduler::do_schedule_sealed(
+fn sealed_chain(n: u8) -> BoundedVec<SealedCiphertextOf<Test>, <Test as Config>::MaxSealedOccurrences> {
+	(0..n)
+		.map(|i| SealedCiphertext::Inline(vec![i; 32].try_into().unwrap()))
+		.collect::<Vec<_>>()
+		.try_into()
+		.unwrap()
+}
+
+#[test]
+fn schedule_named_sealed_checks_the_chain() {
+	new_test_ext().execute_with(|| {
+		let weight = Weight::from_parts(100, 0);
+		// no ciphertexts
+		assert_noop!(
+			Scheduler::schedule_named_sealed(
+				RuntimeOrigin::root(), [1u8; 32], 4, Some(2), 127, sealed_chain(0), weight,
+			),
+			Error::<Test>::InvalidSealedChain,
+		);
+		// several occurrences without a period
+		assert_noop!(
+			Scheduler::schedule_named_sealed(
+				RuntimeOrigin::root(), [1u8; 32], 4, None, 127, sealed_chain(3), weight,
+			),
+			Error::<Test>::InvalidSealedChain,
+		);
+		// several occurrences with a zero period
+		assert_noop!(
+			Scheduler::schedule_named_sealed(
+				RuntimeOrigin::root(), [1u8; 32], 4, Some(0), 127, sealed_chain(3), weight,
+			),
+			Error::<Test>::InvalidSealedChain,
+		);
+
+		assert_ok!(Scheduler::schedule_named_sealed(
+			RuntimeOrigin::root(), [1u8; 32], 4, Some(2), 127, sealed_chain(3), weight,
+		));
+		assert_eq!(Lookup::<Test>::get([1u8; 32]), Some((4, 0)));
+		assert_eq!(SealedChains::<Test>::get([1u8; 32]).unwrap().len(), 2);
+		// names are unique
+		assert_noop!(
+			Scheduler::schedule_named_sealed(
+				RuntimeOrigin::root(), [1u8; 32], 5, None, 127, sealed_chain(1), weight,
+			),
+			Error::<Test>::FailedToSchedule,
+		);
+	});
+}
+
+#[test]
+fn periodic_sealed_task_moves_on_to_its_next_ciphertext() {
+	new_test_ext().execute_with(|| {
+		let id = [1u8; 32];
+		assert_ok!(Scheduler::schedule_named_sealed(
+			RuntimeOrigin::root(),
+			id,
+			4,
+			Some(2),
+			127,
+			sealed_chain(3),
+			Weight::from_parts(100, 0),
+		));
+
+		// no pulses are ever produced, so the first occurrence expires at 4 + 5
+		run_to_block(9);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			RuntimeEvent::Scheduler(crate::Event::Scheduled { when: 10, index: 0 })
+		);
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::Scheduler(crate::Event::SealedTaskExpired {
+				task: (4, 0),
+				id: Some(id),
+				reason: SealedTaskExpiry::BeaconBehind,
+			})));
+
+		// the next occurrence is still locked to block 6 and only one ciphertext is left
+		assert_eq!(Lookup::<Test>::get(id), Some((10, 0)));
+		assert_eq!(SealedTasks::<Test>::get(id).unwrap().target, 6);
+		assert_eq!(SealedChains::<Test>::get(id).unwrap().len(), 1);
+		let task = Agenda::<Test>::get(10)[0].clone().unwrap();
+		assert_eq!(
+			task.maybe_ciphertext,
+			Some(SealedCiphertext::Inline(vec![1u8; 32].try_into().unwrap()))
+		);
+		assert_eq!(task.maybe_periodic, Some((2, 1)));
+
+		// canceling by name removes every remaining occurrence
+		assert_ok!(Scheduler::cancel_named(RuntimeOrigin::root(), id));
+		assert!(SealedTasks::<Test>::get(id).is_none());
+		assert!(SealedChains::<Test>::get(id).is_none());
+		run_to_block(30);
+		assert!(Lookup::<Test>::get(id).is_none());
+		assert!(logger::log().is_empty());
+	});
+}
+
+#[test]
+fn sealed_task_requests_its_ciphertext_preimage() {
+	new_test_ext().execute_with(|| {
+		let hash = <Test as frame_system::Config>::Hashing::hash(&[7u8; 5000]);
+		let ciphertext = SealedCiphertext::Lookup { hash, len: 5000 };
+		let id = ciphertext.task_name(&root());
+		assert_ok!(Scheduler::schedule_sealed(
+			RuntimeOrigin::root(),
+			4,
+			127,
+			ciphertext.clone(),
+			Weight::from_parts(100, 0),
+		));
+		assert_eq!(Lookup::<Test>::get(id), Some((4, 0)));
+		assert!(Preimage::is_requested(&hash));
+		assert_eq!(Agenda::<Test>::get(4)[0].clone().unwrap().maybe_ciphertext, Some(ciphertext));
+
+		// the preimage is no longer needed once the task is canceled
+		assert_ok!(Scheduler::cancel_sealed(RuntimeOrigin::root(), id));
+		assert!(!Preimage::is_requested(&hash));
+	});
+}
+
+#[test]
+fn agenda_entries_report_sealed_and_named_tasks() {
+	new_test_ext().execute_with(|| {
+		let call =
+			RuntimeCall::Logger(LoggerCall::log { i: 42, weight: Weight::from_parts(10, 0) });
+		assert_ok!(Scheduler::do_schedule_named(
+			[1u8; 32],
 			DispatchTime::At(4),
+			Some((3, 2)),
 			127,
 			root(),
+			Preimage::bound(call).unwrap(),
+		));
+		let ciphertext = SealedCiphertext::Inline(vec![1; 32].try_into().unwrap());
+		let id = ciphertext.task_name(&root());
+		assert_ok!(Scheduler::schedule_sealed(
+			RuntimeOrigin::root(),
+			5,
+			10,
 			ciphertext,
+			Weight::from_parts(100, 0),
 		));
 
-		// `log` runtime call should not have executed yet
-		run_to_block(3);
+		let entries = Scheduler::agenda_entries(1, 10);
+		assert_eq!(
+			entries,
+			vec![
+				AgendaEntry {
+					address: (4, 0),
+					maybe_id: Some([1u8; 32]),
+					priority: 127,
+					origin: root(),
+					maybe_periodic: Some((3, 2)),
+					sealed: false,
+					ciphertext_len: None,
+				},
+				AgendaEntry {
+					address: (5, 0),
+					maybe_id: Some(id),
+					priority: 10,
+					origin: root(),
+					maybe_periodic: None,
+					sealed: true,
+					ciphertext_len: Some(32),
+				},
+			]
+		);
+		assert!(Scheduler::agenda_entries(5, 4).is_empty());
+		assert_eq!(Scheduler::task_address(id), Some((5, 0)));
+		assert_eq!(Scheduler::incomplete_since(), None);
+	});
+}
+
+#[test]
+fn shuffled_agenda_keeps_priorities_in_order() {
+	new_test_ext().execute_with(|| {
+		let ordered: Vec<(u32, u8)> = vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 5), (5, 5), (6, 9)];
+		let seed = MockRandomness::random_for(b"scheduler", 0).unwrap();
+		let mut shuffled = ordered.clone();
+		Scheduler::shuffle_within_priorities(seed, &mut shuffled);
+
+		// priorities are still serviced in order, only the tasks within a priority move
+		let priorities = |v: &[(u32, u8)]| v.iter().map(|k| k.1).collect::<Vec<_>>();
+		assert_eq!(priorities(&shuffled), priorities(&ordered));
+		let mut indices = shuffled.iter().map(|k| k.0).collect::<Vec<_>>();
+		indices[..4].sort();
+		indices[4..6].sort();
+		assert_eq!(indices, (0..7).collect::<Vec<_>>());
+
+		// the order is deterministic for a given agenda
+		let mut again = ordered.clone();
+		Scheduler::shuffle_within_priorities(seed, &mut again);
+		assert_eq!(again, shuffled);
+	});
+}
+
+/// Schedule a sealed task that logs `i` at `when`, locked to the pulse of `when`.
+fn schedule_sealed_log(when: u64, i: u32) {
+	let call = RuntimeCall::Logger(LoggerCall::log { i, weight: Weight::from_parts(10, 0) });
+	assert_ok!(Scheduler::do_schedule_sealed(
+		DispatchTime::At(when),
+		127,
+		root(),
+		SealedCiphertext::Inline(seal(when, &call.encode())),
+		Weight::from_parts(100, 0),
+		None,
+	));
+}
+
+#[test]
+fn set_agenda_ordering_works() {
+	new_test_ext().execute_with(|| {
+		run_to_block(2);
+		assert_noop!(
+			Scheduler::set_agenda_ordering(RuntimeOrigin::root(), 2, TaskOrdering::Shuffled),
+			Error::<Test>::TargetBlockNumberInPast,
+		);
+		// an origin that may schedule may not hold back the agenda of others
+		assert_noop!(
+			Scheduler::set_agenda_ordering(RuntimeOrigin::signed(1), 4, TaskOrdering::Shuffled),
+			BadOrigin,
+		);
+		assert_ok!(Scheduler::set_agenda_ordering(RuntimeOrigin::root(), 4, TaskOrdering::Shuffled));
+		assert_eq!(AgendaOrdering::<Test>::get(4), TaskOrdering::Shuffled);
+		// a shuffled agenda stays shuffled
+		assert_noop!(
+			Scheduler::set_agenda_ordering(RuntimeOrigin::root(), 4, TaskOrdering::Priority),
+			Error::<Test>::OrderingLoosened,
+		);
+		assert_ok!(Scheduler::set_agenda_ordering(RuntimeOrigin::root(), 4, TaskOrdering::Shuffled));
+
+		for i in 0..3 {
+			let call =
+				RuntimeCall::Logger(LoggerCall::log { i, weight: Weight::from_parts(10, 0) });
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4),
+				None,
+				127,
+				root(),
+				Preimage::bound(call).unwrap()
+			));
+		}
+		for i in 10..13 {
+			schedule_sealed_log(4, i);
+		}
+
+		// tasks that are not sealed are serviced by priority without waiting for the pulse
+		run_to_block(5);
+		assert_eq!(logger::log().into_iter().map(|(_, i)| i).collect::<Vec<_>>(), vec![0, 1, 2]);
+
+		// the sealed tasks are serviced in an order drawn from the pulse for block 4
+		Latest::set(4);
+		run_to_block(6);
+		let seed = MockRandomness::random_for(b"scheduler", 4).unwrap();
+		let mut expected = vec![(3u32, 127u8), (4, 127), (5, 127)];
+		Scheduler::shuffle_within_priorities(seed, &mut expected);
+		let logged = logger::log().into_iter().map(|(_, i)| i).skip(3).collect::<Vec<_>>();
+		assert_eq!(logged, expected.into_iter().map(|(i, _)| i + 7).collect::<Vec<_>>());
+		// the ordering is dropped along with the agenda
+		assert_eq!(AgendaOrdering::<Test>::get(4), TaskOrdering::Priority);
+	});
+}
+
+#[test]
+fn shuffling_an_agenda_keeps_the_places_of_tasks_that_are_not_sealed() {
+	new_test_ext().execute_with(|| {
+		Latest::set(4);
+		for i in 0..6 {
+			if i % 2 == 0 {
+				let call =
+					RuntimeCall::Logger(LoggerCall::log { i, weight: Weight::from_parts(10, 0) });
+				assert_ok!(Scheduler::do_schedule(
+					DispatchTime::At(4),
+					None,
+					127,
+					root(),
+					Preimage::bound(call).unwrap()
+				));
+			} else {
+				schedule_sealed_log(4, i);
+			}
+		}
+		let agenda = Agenda::<Test>::get(4);
+		let seed = MockRandomness::random_for(b"scheduler", 4).unwrap();
+		let mut ordered = (0..6).map(|i| (i, 127u8)).collect::<Vec<_>>();
+		Scheduler::shuffle_sealed(seed, &agenda, &mut ordered);
+
+		assert_eq!(
+			ordered.iter().step_by(2).map(|(i, _)| *i).collect::<Vec<_>>(),
+			vec![0, 2, 4],
+		);
+		let mut sealed = ordered.iter().skip(1).step_by(2).map(|(i, _)| *i).collect::<Vec<_>>();
+		sealed.sort();
+		assert_eq!(sealed, vec![1, 3, 5]);
+	});
+}
+
+#[test]
+fn shuffled_agenda_is_serviced_by_priority_once_the_pulse_is_overdue() {
+	new_test_ext().execute_with(|| {
+		// the sealed tasks are locked to block 4 but moved to block 6
+		for i in 0..3 {
+			schedule_sealed_log(4, i);
+			let id = SealedCiphertext::Inline(seal(
+				4,
+				&RuntimeCall::Logger(LoggerCall::log { i, weight: Weight::from_parts(10, 0) })
+					.encode(),
+			))
+			.task_name(&root());
+			assert_ok!(Scheduler::reschedule_sealed(RuntimeOrigin::root(), id, 6));
+		}
+		assert_ok!(Scheduler::set_agenda_ordering(RuntimeOrigin::root(), 6, TaskOrdering::Shuffled));
+		// the tasks can be decrypted, but the pulse for block 6 never comes
+		Latest::set(4);
+
+		// SealedTaskExpiry = 5
+		run_to_block(10);
 		assert!(logger::log().is_empty());
+		run_to_block(11);
+		assert_eq!(logger::log().into_iter().map(|(_, i)| i).collect::<Vec<_>>(), vec![0, 1, 2]);
+	});
+}
+
+#[test]
+fn decrypted_sealed_task_is_not_decrypted_again() {
+	new_test_ext().execute_with(|| {
+		let call =
+			RuntimeCall::Logger(LoggerCall::log { i: 42, weight: Weight::from_parts(10, 0) });
+		// a sealed task whose dispatch was postponed after its call was recovered
+		let task = Scheduled {
+			maybe_id: None,
+			priority: 127,
+			maybe_call: Some(Preimage::bound(call).unwrap()),
+			maybe_ciphertext: Some(SealedCiphertext::Inline(vec![0u8; 32].try_into().unwrap())),
+			maybe_periodic: None,
+			origin: root(),
+			_phantom: Default::default(),
+		};
+		Agenda::<Test>::insert(4, BoundedVec::truncate_from(vec![Some(task)]));
 
+		// the ciphertext cannot be decrypted, but the cached call is dispatched
+		run_to_block(4);
+		assert_eq!(logger::log(), vec![(root(), 42u32)]);
+		assert!(!System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::Scheduler(crate::Event::SealedTaskDecryptionFailed { .. })
+		)));
+		assert!(Agenda::<Test>::get(4).is_empty());
+	});
+}
 
-		// now cancel
-		assert_ok!(Scheduler::do_cancel(
-			None, (4, 0),
-		));
+#[test]
+fn canceling_a_decrypted_sealed_task_drops_its_call() {
+	new_test_ext().execute_with(|| {
+		// too long to be inlined, so the bound call is noted as a preimage
+		let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![0u8; 256] });
+		let bound = Preimage::bound(call).unwrap();
+		let hash = bound.hash();
+		assert!(Preimage::len(&hash).is_some());
+		let task = Scheduled {
+			maybe_id: None,
+			priority: 127,
+			maybe_call: Some(bound),
+			maybe_ciphertext: Some(SealedCiphertext::Inline(vec![0u8; 32].try_into().unwrap())),
+			maybe_periodic: None,
+			origin: root(),
+			_phantom: Default::default(),
+		};
+		Agenda::<Test>::insert(4, BoundedVec::truncate_from(vec![Some(task)]));
+
+		assert_ok!(Scheduler::do_cancel(None, (4, 0)));
+		assert!(Preimage::len(&hash).is_none());
+		assert!(Agenda::<Test>::get(4).is_empty());
+	});
+}
+
+#[test]
+fn migrate_v4_to_v5_keeps_upstream_agendas() {
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
 
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(4).put::<Scheduler>();
+		let call = Preimage::bound(RuntimeCall::Logger(LoggerCall::log {
+			i: 42,
+			weight: Weight::from_parts(10, 0),
+		}))
+		.unwrap();
+		let old = vec![
+			Some(migrations::v4::Scheduled {
+				maybe_id: Some([1u8; 32]),
+				priority: 127,
+				call: call.clone(),
+				maybe_periodic: Some((3, 2)),
+				origin: root(),
+				_phantom: Default::default(),
+			}),
+			None,
+		];
+		migrations::v4::Agenda::<Test>::insert(4, BoundedVec::truncate_from(old));
+
+		migrations::v5::MigrateV4ToV5::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Scheduler::on_chain_storage_version(), StorageVersion::new(5));
+		assert_eq!(
+			Agenda::<Test>::get(4).into_inner(),
+			vec![
+				Some(ScheduledOf::<Test> {
+					maybe_id: Some([1u8; 32]),
+					priority: 127,
+					maybe_call: Some(call.clone()),
+					maybe_ciphertext: None,
+					maybe_periodic: Some((3, 2)),
+					origin: root(),
+					_phantom: Default::default(),
+				}),
+				None,
+			]
+		);
+
+		// a second run leaves the migrated agendas alone
+		migrations::v5::MigrateV4ToV5::<Test>::on_runtime_upgrade();
+		assert_eq!(Agenda::<Test>::get(4)[0].clone().unwrap().maybe_call, Some(call));
+	});
+}
+
+#[test]
+fn migrate_v4_to_v5_keeps_inline_ciphertexts() {
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(4).put::<Scheduler>();
+		let ciphertext: Ciphertext = seal(4, b"sealed-call");
+		let old = vec![
+			None,
+			Some(migrations::v4::CiphertextScheduledOf::<Test> {
+				maybe_id: None,
+				priority: 127,
+				maybe_call: None,
+				maybe_ciphertext: Some(ciphertext.clone()),
+				maybe_periodic: None,
+				origin: root(),
+				_phantom: Default::default(),
+			}),
+		];
+		migrations::v4::CiphertextAgenda::<Test>::insert(4, BoundedVec::truncate_from(old));
+
+		migrations::v5::MigrateCiphertextsV4ToV5::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Scheduler::on_chain_storage_version(), StorageVersion::new(5));
+		let ciphertext = SealedCiphertext::Inline(ciphertext);
+		let id = ciphertext.task_name(&root());
+		assert_eq!(
+			Agenda::<Test>::get(4).into_inner(),
+			vec![
+				None,
+				Some(ScheduledOf::<Test> {
+					maybe_id: Some(id),
+					priority: 127,
+					maybe_call: None,
+					maybe_ciphertext: Some(ciphertext),
+					maybe_periodic: None,
+					origin: root(),
+					_phantom: Default::default(),
+				}),
+			]
+		);
+		// the task can be found, canceled and rescheduled like any other sealed task
+		assert_eq!(Lookup::<Test>::get(id), Some((4, 1)));
+		assert_eq!(
+			SealedTasks::<Test>::get(id),
+			Some(SealedTaskInfo { deposit: None, max_weight: MaximumSchedulerWeight::get(), target: 4 }),
+		);
+		assert_noop!(
+			Scheduler::reschedule_sealed(RuntimeOrigin::root(), id, 3),
+			Error::<Test>::RescheduleBeforeTarget,
+		);
+
+		// and it is decrypted once the pulse for its block is known
+		Latest::set(4);
 		run_to_block(4);
-		assert!(logger::log().is_empty());
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::Scheduler(crate::Event::SealedTaskDecryptionFailed { .. })
+		)));
+		assert_eq!(SealedTasks::<Test>::get(id), None);
 	});
-}
\ No newline at end of file
+}